@@ -0,0 +1,103 @@
+//! 挂载点最长前缀匹配的基准测试：前缀树索引与逐个扫描映射表对比
+
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use samevol::matching::{MountIndex, PathMatcher};
+
+/// 构造类似构建服务器的挂载布局：系统挂载点、容器 overlay 挂载点和绑定挂载
+fn mount_points(count: usize) -> Vec<OsString> {
+    let mut mount_points: Vec<OsString> = ["/", "/proc/", "/sys/", "/dev/", "/run/", "/home/"]
+        .into_iter()
+        .map(OsString::from)
+        .collect();
+    for i in 0..count {
+        let mount_point = match i % 2 {
+            0 => format!("/var/lib/docker/overlay2/{:064x}/merged/", i),
+            _ => format!("/srv/jobs/{}/workspace/", i),
+        };
+        mount_points.push(mount_point.into());
+    }
+    mount_points
+}
+
+/// 待查询路径：一半位于嵌套挂载点之下，一半位于根文件系统
+fn paths(count: usize) -> Vec<OsString> {
+    (0..256)
+        .map(|i| {
+            let n = i * 7 % count.max(1);
+            match i % 4 {
+                0 => format!("/var/lib/docker/overlay2/{:064x}/merged/usr/lib/x86_64-linux-gnu/libc.so.6", n - n % 2),
+                1 => format!("/srv/jobs/{}/workspace/target/debug/deps/samevol-{:016x}.rlib", n | 1, i),
+                2 => format!("/home/build/.cargo/registry/src/index/crate-{}/src/lib.rs", i),
+                _ => format!("/usr/share/doc/package-{}/changelog.gz", i),
+            }
+            .into()
+        })
+        .collect()
+}
+
+/// 原有实现：扫描全部键，收集所有前缀匹配后取最长者
+fn linear_scan<'a>(map: &'a HashMap<OsString, usize>, mount_point: &OsString) -> Option<&'a usize> {
+    let mount_point = mount_point.as_encoded_bytes();
+    let candidates = map.keys()
+        .filter(|k| mount_point.starts_with(k.as_encoded_bytes()))
+        .collect::<Vec<_>>();
+    let mount_path = candidates.iter().max_by_key(|k| k.len())?;
+    map.get(*mount_path)
+}
+
+fn bench_lookup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("longest_match");
+
+    for count in [10, 100, 1000] {
+        let mount_points = mount_points(count);
+        // 与 Linux 后端一致，查询的路径以分隔符结尾
+        let paths: Vec<OsString> = paths(count)
+            .into_iter()
+            .map(|mut path| {
+                path.push("/");
+                path
+            })
+            .collect();
+
+        let map: HashMap<OsString, usize> = mount_points.iter().cloned().zip(0..).collect();
+        let mut index = MountIndex::new(PathMatcher::unix());
+        for (mount_point, value) in mount_points.iter().zip(0..) {
+            index.insert(mount_point, value);
+        }
+
+        // 两种实现的结果必须一致
+        for path in &paths {
+            assert_eq!(linear_scan(&map, path), index.longest_match(path));
+        }
+
+        group.bench_with_input(BenchmarkId::new("linear_scan", count), &paths, |b, paths| {
+            b.iter(|| paths.iter().filter_map(|path| linear_scan(&map, black_box(path))).count())
+        });
+        group.bench_with_input(BenchmarkId::new("trie", count), &paths, |b, paths| {
+            b.iter(|| paths.iter().filter_map(|path| index.longest_match(black_box(path))).count())
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_windows_matcher(c: &mut Criterion) {
+    // NTFS 大小写折叠的额外开销
+    let mut index = MountIndex::new(PathMatcher::windows());
+    index.insert(r"C:\", 0);
+    for i in 0..100 {
+        index.insert(format!(r"C:\Mounts\Volume{}\", i), i + 1);
+    }
+    let path = r"c:\mounts\volume42\Users\Build\Projekte\Übersetzung\ÄÖÜ\file.txt";
+
+    c.bench_function("longest_match/windows_ntfs_case_folding", |b| {
+        b.iter(|| index.longest_match(black_box(path)))
+    });
+}
+
+criterion_group!(benches, bench_lookup, bench_windows_matcher);
+criterion_main!(benches);
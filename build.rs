@@ -1,7 +1,7 @@
 fn main() {
     let target_os = std::env::var("CARGO_CFG_TARGET_OS").unwrap();
 
-    if target_os != "windows" {
-        panic!("This library only supports Windows.");
+    if target_os != "windows" && target_os != "linux" {
+        panic!("This library only supports Windows and Linux.");
     }
 }
@@ -0,0 +1,217 @@
+/*
+ * Copyright 2025 爱佐 (Ayrzo)
+ *
+ * This file is part of cargo crate samevol (https://crates.io/crates/samevol),
+ * which licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! 卷后端抽象：将卷枚举、挂载点查询和完整路径解析与具体平台解耦
+
+use std::ffi::OsString;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::matching::PathMatcher;
+use crate::{VolumeInfo, VolumeMetadata};
+
+/// A source of volume and mount point information.
+///
+/// The crate resolves paths in two steps: the path is first expanded to a full path and
+/// mapped to a mount point by the backend, then the longest matching mount point in the
+/// table built from [`volumes`](VolumeBackend::volumes) yields the device path. Each step
+/// is a method of this trait, so the whole resolution can be redirected, e.g. to a
+/// [`FakeBackend`](crate::FakeBackend) in tests.
+///
+/// The platform implementation is available as [`SystemBackend`].
+///
+/// # Conventions
+/// - Mount points returned by [`volumes`](VolumeBackend::volumes) and
+///   [`volume_mount_point`](VolumeBackend::volume_mount_point) are compared with the
+///   [`PathMatcher`] returned by [`path_matcher`](VolumeBackend::path_matcher), which
+///   takes care of separators, case and component boundaries.
+/// - Device paths are opaque strings; two paths are on the same volume if and only if
+///   their device paths are equal.
+/// - Paths and mount points are passed as [`OsStr`](std::ffi::OsStr)-based types and must
+///   be kept lossless, since NTFS names may contain unpaired surrogates and Linux names
+///   arbitrary bytes.
+pub trait VolumeBackend: Send + Sync {
+    /// Enumerates all volumes together with the mount points of each volume.
+    ///
+    /// Returns a list of `(device path, mount points)` pairs. A volume may have no
+    /// mount point at all, or several of them.
+    fn volumes(&self) -> io::Result<Vec<(String, Vec<OsString>)>>;
+
+    /// Expands a possibly relative path to a full path, like `GetFullPathNameW`.
+    fn full_path(&self, path: &Path) -> io::Result<PathBuf>;
+
+    /// Returns the mount point of the volume containing `full_path`, like
+    /// `GetVolumePathNameW`.
+    ///
+    /// Backends without an equivalent query may return `full_path` itself (with a
+    /// trailing separator) and rely on the longest-prefix match against the table
+    /// returned by [`volumes`](VolumeBackend::volumes).
+    fn volume_mount_point(&self, full_path: &Path) -> io::Result<OsString>;
+
+    /// Returns `true` if the last component of `full_path` is a symbolic link, junction
+    /// or other reparse point, which may lead to another volume than its parent
+    /// directory.
+    ///
+    /// Batch lookups (see [`Resolver::batch`](crate::Resolver::batch)) reuse the mount
+    /// point of a directory for all entries in it, except for links.
+    ///
+    /// Defaults to checking [`std::fs::symlink_metadata`], treating errors (e.g. a path
+    /// that does not exist) as no link. Backends whose
+    /// [`full_path`](VolumeBackend::full_path) already resolves links may return `false`.
+    fn is_link(&self, full_path: &Path) -> bool {
+        is_link(full_path)
+    }
+
+    /// Returns an identifier of the mount `full_path` is reached through, queried live
+    /// instead of taken from the table built from [`volumes`](VolumeBackend::volumes).
+    ///
+    /// Used by [`Granularity::Mount`](crate::Granularity::Mount): two paths are on the
+    /// same mount if the returned identifiers are equal. `full_path` does not need to
+    /// exist, the mount of its nearest existing ancestor is returned.
+    ///
+    /// Defaults to `Ok(None)`, in which case the mount points found in the table are
+    /// compared. Those only reflect mounts created since the table was last built.
+    fn mount_id(&self, full_path: &Path) -> io::Result<Option<u64>> {
+        let _ = full_path;
+        Ok(None)
+    }
+
+    /// Returns how paths are matched against the mount points of this backend.
+    ///
+    /// Defaults to [`PathMatcher::system`]. Backends emulating another platform should
+    /// return the matcher of that platform.
+    fn path_matcher(&self) -> PathMatcher {
+        PathMatcher::system()
+    }
+
+    /// Returns `true` if a file can be renamed between two mount points of the same volume.
+    ///
+    /// On Windows, `MoveFileExW` works anywhere within a volume, even across two of its
+    /// mount points. On Linux, `rename(2)` fails with `EXDEV` across two mounts of the same
+    /// filesystem (e.g. bind mounts), so a rename only works within a single mount.
+    ///
+    /// Defaults to `true` on Windows and `false` elsewhere. Backends emulating another
+    /// platform should return the behavior of that platform.
+    fn renames_across_mount_points(&self) -> bool {
+        cfg!(windows)
+    }
+
+    /// Returns the identity of the block device backing a volume.
+    ///
+    /// Used by [`Granularity::BlockDevice`](crate::Granularity::BlockDevice). Two volumes
+    /// are on the same block device if the returned identities are equal; their format is
+    /// up to the backend.
+    ///
+    /// Defaults to the device path of the volume itself, which is right for backends
+    /// where every volume is a block device of its own.
+    fn block_device(&self, volume: &VolumeInfo) -> io::Result<String> {
+        Ok(volume.id().as_str().to_owned())
+    }
+
+    /// Returns the identities of the physical disks backing a volume.
+    ///
+    /// Used by [`Granularity::PhysicalDisk`](crate::Granularity::PhysicalDisk). A volume
+    /// may span several disks. Two volumes are on the same physical disk if the returned
+    /// lists share an identity; their format is up to the backend.
+    ///
+    /// Defaults to an [`io::ErrorKind::Unsupported`] error.
+    fn physical_disks(&self, volume: &VolumeInfo) -> io::Result<Vec<String>> {
+        let _ = volume;
+        Err(io::Error::new(io::ErrorKind::Unsupported, "physical disks are not supported by this backend"))
+    }
+
+    /// Queries the filesystem attributes and capacity of a volume, like
+    /// `GetVolumeInformationW` and `GetDiskFreeSpaceExW`.
+    ///
+    /// `volume` is an entry of the table built from [`volumes`](VolumeBackend::volumes).
+    /// Attributes the backend cannot determine are left unset.
+    ///
+    /// Defaults to an [`io::ErrorKind::Unsupported`] error.
+    fn volume_metadata(&self, volume: &VolumeInfo) -> io::Result<VolumeMetadata> {
+        let _ = volume;
+        Err(io::Error::new(io::ErrorKind::Unsupported, "volume metadata is not supported by this backend"))
+    }
+}
+
+impl<B: VolumeBackend + ?Sized> VolumeBackend for Arc<B> {
+    fn volumes(&self) -> io::Result<Vec<(String, Vec<OsString>)>> {
+        (**self).volumes()
+    }
+
+    fn full_path(&self, path: &Path) -> io::Result<PathBuf> {
+        (**self).full_path(path)
+    }
+
+    fn volume_mount_point(&self, full_path: &Path) -> io::Result<OsString> {
+        (**self).volume_mount_point(full_path)
+    }
+
+    fn is_link(&self, full_path: &Path) -> bool {
+        (**self).is_link(full_path)
+    }
+
+    fn mount_id(&self, full_path: &Path) -> io::Result<Option<u64>> {
+        (**self).mount_id(full_path)
+    }
+
+    fn path_matcher(&self) -> PathMatcher {
+        (**self).path_matcher()
+    }
+
+    fn renames_across_mount_points(&self) -> bool {
+        (**self).renames_across_mount_points()
+    }
+
+    fn block_device(&self, volume: &VolumeInfo) -> io::Result<String> {
+        (**self).block_device(volume)
+    }
+
+    fn physical_disks(&self, volume: &VolumeInfo) -> io::Result<Vec<String>> {
+        (**self).physical_disks(volume)
+    }
+
+    fn volume_metadata(&self, volume: &VolumeInfo) -> io::Result<VolumeMetadata> {
+        (**self).volume_metadata(volume)
+    }
+}
+
+/// 判断路径的最后一个组件是否为符号链接或重解析点（目录联接、卷挂载点等）
+fn is_link(path: &Path) -> bool {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return false;
+    };
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::MetadataExt as _;
+
+        const FILE_ATTRIBUTE_REPARSE_POINT: u32 = 0x400;
+        if metadata.file_attributes() & FILE_ATTRIBUTE_REPARSE_POINT != 0 {
+            return true;
+        }
+    }
+    metadata.file_type().is_symlink()
+}
+
+/// The volume backend of the current platform.
+#[cfg(windows)]
+pub type SystemBackend = crate::windows::WindowsBackend;
+
+/// The volume backend of the current platform.
+#[cfg(target_os = "linux")]
+pub type SystemBackend = crate::linux::LinuxBackend;
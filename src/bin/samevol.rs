@@ -0,0 +1,315 @@
+/*
+ * Copyright 2025 爱佐 (Ayrzo)
+ *
+ * This file is part of cargo crate samevol (https://crates.io/crates/samevol),
+ * which licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! The `samevol` command-line tool.
+//!
+//! ```text
+//! samevol check <PATH1> <PATH2>   Exit code 0 if on the same volume, 1 if not, 2 on error
+//! samevol which <PATH>            Print the volume device path of a path
+//! samevol list                    List the volumes and their mount points
+//! samevol refresh                 Rebuild the volume mapping table
+//! samevol batch [-0] [--pairs]    Resolve the paths read from stdin, as JSON Lines
+//! ```
+//!
+//! Every subcommand accepts `--json` to print a JSON object instead of text.
+//!
+//! `batch` reads newline-separated paths (NUL-separated with `-0`, e.g. from
+//! `find -print0`) and writes one JSON object per path, all resolved against the same
+//! snapshot of the volume mapping table:
+//!
+//! ```text
+//! {"device_path":"8:1","error":null,"mount_point":"/","path":"/etc/hosts"}
+//! {"device_path":null,"error":"...","mount_point":null,"path":"/missing"}
+//! ```
+//!
+//! With `--pairs`, consecutive paths are compared with each other instead, writing
+//! `{"error":null,"paths":[...],"same":true}` per pair, with one object as above per path.
+
+use std::ffi::OsString;
+use std::io::{self, BufRead as _, BufWriter, Write as _};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Arg, ArgAction, ArgMatches, Command, value_parser};
+use serde_json::{Value, json};
+
+use samevol::{Batch, Error, Resolution, Resolver};
+
+/// 退出码：位于同一卷或执行成功
+const EXIT_SAME: u8 = 0;
+/// 退出码：位于不同卷
+const EXIT_DIFFERENT: u8 = 1;
+/// 退出码：出错（与 clap 参数错误的退出码一致）
+const EXIT_ERROR: u8 = 2;
+
+/// 子命令的执行结果，按输出格式分别呈现
+struct Report {
+    code: u8,
+    text: String,
+    json: Value,
+}
+
+fn main() -> ExitCode {
+    let matches = command().get_matches();
+    let json = matches.get_flag("json");
+    let resolver = Resolver::new();
+
+    let result = match matches.subcommand() {
+        Some(("check", args)) => check(&resolver, args),
+        Some(("which", args)) => which(&resolver, args),
+        Some(("list", _)) => list(&resolver),
+        Some(("refresh", _)) => refresh(&resolver),
+        Some(("batch", args)) => return batch(&resolver, args),
+        _ => unreachable!("subcommand is required"),
+    };
+
+    match result {
+        Ok(report) => {
+            if json {
+                println!("{}", report.json);
+            } else if !report.text.is_empty() {
+                println!("{}", report.text);
+            }
+            ExitCode::from(report.code)
+        }
+        Err(e) => {
+            if json {
+                println!("{}", error_json(&e));
+            } else {
+                eprintln!("samevol: {}", e);
+            }
+            ExitCode::from(EXIT_ERROR)
+        }
+    }
+}
+
+/// 构建命令行定义
+fn command() -> Command {
+    let path = |name: &'static str| Arg::new(name).required(true).value_parser(value_parser!(PathBuf));
+
+    Command::new("samevol")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Determine whether paths reside on the same storage volume")
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Print the result as JSON"),
+        )
+        .subcommand(
+            Command::new("check")
+                .about("Exit with 0 if both paths are on the same volume, 1 if not, 2 on error")
+                .arg(path("PATH1"))
+                .arg(path("PATH2")),
+        )
+        .subcommand(Command::new("which").about("Print the volume device path of a path").arg(path("PATH")))
+        .subcommand(Command::new("list").about("List the volumes and their mount points"))
+        .subcommand(Command::new("refresh").about("Rebuild the volume mapping table and print its size"))
+        .subcommand(
+            Command::new("batch")
+                .about("Resolve the paths read from stdin and print one JSON object per line")
+                .arg(
+                    Arg::new("null")
+                        .short('0')
+                        .long("null")
+                        .action(ArgAction::SetTrue)
+                        .help("Paths are separated by NUL instead of newline"),
+                )
+                .arg(
+                    Arg::new("pairs")
+                        .long("pairs")
+                        .action(ArgAction::SetTrue)
+                        .help("Compare each two consecutive paths"),
+                ),
+        )
+}
+
+/// `check`：比较两个路径是否位于同一卷
+fn check(resolver: &Resolver, args: &ArgMatches) -> Result<Report, Error> {
+    let path1 = path_arg(args, "PATH1");
+    let path2 = path_arg(args, "PATH2");
+    let volume1 = resolver.try_resolve_device_path(path1)?;
+    let volume2 = resolver.try_resolve_device_path(path2)?;
+    let same = volume1 == volume2;
+
+    Ok(Report {
+        code: if same { EXIT_SAME } else { EXIT_DIFFERENT },
+        text: if same { "same".to_owned() } else { "different".to_owned() },
+        json: json!({
+            "same": same,
+            "paths": [
+                { "path": path1.to_string_lossy(), "device_path": volume1 },
+                { "path": path2.to_string_lossy(), "device_path": volume2 },
+            ],
+        }),
+    })
+}
+
+/// `which`：输出路径所在卷的设备路径
+fn which(resolver: &Resolver, args: &ArgMatches) -> Result<Report, Error> {
+    let path = path_arg(args, "PATH");
+    let device_path = resolver.try_resolve_device_path(path)?;
+
+    Ok(Report {
+        code: EXIT_SAME,
+        json: json!({ "path": path.to_string_lossy(), "device_path": device_path }),
+        text: device_path,
+    })
+}
+
+/// `list`：输出所有卷及其挂载点，每个挂载点一行
+fn list(resolver: &Resolver) -> Result<Report, Error> {
+    let volumes = resolver.volumes()?;
+
+    let mut lines = Vec::new();
+    let mut entries = Vec::with_capacity(volumes.len());
+    for volume in &volumes {
+        let mount_points: Vec<String> = volume.mount_points().iter().map(ToString::to_string).collect();
+        if mount_points.is_empty() {
+            lines.push(volume.id().to_string());
+        }
+        lines.extend(mount_points.iter().map(|mount_point| format!("{}\t{}", volume.id(), mount_point)));
+        entries.push(json!({ "device_path": volume.id().as_str(), "mount_points": mount_points }));
+    }
+
+    Ok(Report { code: EXIT_SAME, text: lines.join("\n"), json: json!({ "volumes": entries }) })
+}
+
+/// `refresh`：重建卷映射表
+fn refresh(resolver: &Resolver) -> Result<Report, Error> {
+    let mappings = resolver.reinitialize()?;
+
+    Ok(Report {
+        code: EXIT_SAME,
+        text: format!("{} volume mappings", mappings),
+        json: json!({ "mappings": mappings }),
+    })
+}
+
+/// `batch`：从标准输入流式读取路径，逐行输出 JSON
+///
+/// 单个路径的解析错误写入对应的输出行；只有读写失败或 `--pairs` 时路径个数为奇数才以出错退出。
+fn batch(resolver: &Resolver, args: &ArgMatches) -> ExitCode {
+    let separator = if args.get_flag("null") { b'\0' } else { b'\n' };
+    let pairs = args.get_flag("pairs");
+
+    let mut batch = resolver.batch();
+    let mut output = BufWriter::new(io::stdout().lock());
+    let mut pending: Option<PathBuf> = None;
+
+    let result = (|| -> io::Result<bool> {
+        for record in io::stdin().lock().split(separator) {
+            let Some(path) = record_path(record?, separator) else { continue };
+            let line = match (pairs, pending.take()) {
+                (false, _) => path_json(&path, &batch.resolve(&path)),
+                (true, None) => {
+                    pending = Some(path);
+                    continue;
+                }
+                (true, Some(first)) => pair_json(&mut batch, &first, &path),
+            };
+            writeln!(output, "{}", line)?;
+        }
+
+        // 成对比较时多出的最后一个路径
+        if let Some(path) = pending {
+            let paths = [path_json(&path, &batch.resolve(&path))];
+            writeln!(output, "{}", json!({ "same": null, "error": "missing second path of the pair", "paths": paths }))?;
+            output.flush()?;
+            return Ok(false);
+        }
+        output.flush()?;
+        Ok(true)
+    })();
+
+    match result {
+        Ok(true) => ExitCode::from(EXIT_SAME),
+        Ok(false) => ExitCode::from(EXIT_ERROR),
+        // 下游提前关闭管道（如 `| head`）时安静退出
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => ExitCode::from(EXIT_SAME),
+        Err(e) => {
+            eprintln!("samevol: {}", e);
+            ExitCode::from(EXIT_ERROR)
+        }
+    }
+}
+
+/// 将输入记录转换为路径，忽略空记录；换行分隔时去掉 Windows 风格的行尾 `\r`
+fn record_path(mut record: Vec<u8>, separator: u8) -> Option<PathBuf> {
+    // 以 NUL 分隔时记录按原样使用，`\r` 可能是文件名的一部分
+    if separator == b'\n' && record.last() == Some(&b'\r') {
+        record.pop();
+    }
+    if record.is_empty() {
+        return None;
+    }
+
+    #[cfg(unix)]
+    let path = <OsString as std::os::unix::ffi::OsStringExt>::from_vec(record);
+    // 其他平台上的路径按 UTF-8 读取
+    #[cfg(not(unix))]
+    let path = OsString::from(String::from_utf8_lossy(&record).into_owned());
+    Some(PathBuf::from(path))
+}
+
+/// 成对比较两个路径的 JSON 表示
+fn pair_json(batch: &mut Batch<'_>, path1: &Path, path2: &Path) -> Value {
+    let result1 = batch.resolve(path1);
+    let result2 = batch.resolve(path2);
+    let (same, error) = match (&result1, &result2) {
+        (Ok(resolution1), Ok(resolution2)) => (Some(resolution1.volume() == resolution2.volume()), None),
+        (Err(e), _) | (_, Err(e)) => (None, Some(e.to_string())),
+    };
+    json!({ "same": same, "error": error, "paths": [path_json(path1, &result1), path_json(path2, &result2)] })
+}
+
+/// 单个路径解析结果的 JSON 表示
+fn path_json(path: &Path, result: &Result<Resolution, Error>) -> Value {
+    match result {
+        Ok(resolution) => json!({
+            "path": path.to_string_lossy(),
+            "device_path": resolution.volume().as_str(),
+            "mount_point": resolution.mount_point().to_string(),
+            "error": null,
+        }),
+        Err(e) => json!({
+            "path": path.to_string_lossy(),
+            "device_path": null,
+            "mount_point": null,
+            "error": e.to_string(),
+        }),
+    }
+}
+
+/// 取出必填的路径参数
+fn path_arg<'a>(args: &'a ArgMatches, name: &str) -> &'a Path {
+    args.get_one::<PathBuf>(name).expect("required argument")
+}
+
+/// 错误的 JSON 表示
+fn error_json(e: &Error) -> Value {
+    json!({
+        "error": {
+            "kind": format!("{:?}", e.kind()),
+            "message": e.to_string(),
+            "path": e.path().map(|path| path.to_string_lossy()),
+        },
+    })
+}
@@ -0,0 +1,182 @@
+/*
+ * Copyright 2025 爱佐 (Ayrzo)
+ *
+ * This file is part of cargo crate samevol (https://crates.io/crates/samevol),
+ * which licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! 错误类型：区分“不在同一卷”与“无法判断”的各种原因
+
+use std::error::Error as StdError;
+use std::ffi::OsString;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::VolumeId;
+
+/// The error type of the fallible (`try_`) functions of this crate.
+///
+/// Each variant corresponds to a distinct reason why a path could not be resolved to a
+/// volume, so callers can tell "different volume" apart from "couldn't determine".
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The path (or a required part of it) does not exist.
+    NotFound {
+        /// The offending path, as given by the caller.
+        path: PathBuf,
+        /// The underlying OS error.
+        source: io::Error,
+    },
+    /// Access to the path was denied.
+    PermissionDenied {
+        /// The offending path, as given by the caller.
+        path: PathBuf,
+        /// The underlying OS error.
+        source: io::Error,
+    },
+    /// The path could not be expanded or mapped to a mount point for another reason,
+    /// e.g. because it is malformed.
+    InvalidPath {
+        /// The offending path, as given by the caller.
+        path: PathBuf,
+        /// The underlying OS error.
+        source: io::Error,
+    },
+    /// Enumerating the volumes of the system to build the volume mapping table failed.
+    VolumeEnumeration {
+        /// The underlying OS error.
+        source: io::Error,
+    },
+    /// The mount point of the path does not match any volume in the mapping table.
+    NoMountPoint {
+        /// The offending path, as given by the caller.
+        path: PathBuf,
+        /// The mount point reported for the path.
+        mount_point: OsString,
+    },
+    /// Querying the attributes, capacity or backing devices of a volume failed.
+    Metadata {
+        /// The volume whose metadata was queried.
+        volume: VolumeId,
+        /// The underlying OS error.
+        source: io::Error,
+    },
+}
+
+impl Error {
+    /// 根据 I/O 错误类型将路径相关的错误归类
+    pub(crate) fn from_path_error(path: &Path, source: io::Error) -> Self {
+        let path = path.to_path_buf();
+        match source.kind() {
+            io::ErrorKind::NotFound => Error::NotFound { path, source },
+            io::ErrorKind::PermissionDenied => Error::PermissionDenied { path, source },
+            _ => Error::InvalidPath { path, source },
+        }
+    }
+
+    /// Returns the offending path, if the error is related to a specific path.
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Error::NotFound { path, .. }
+            | Error::PermissionDenied { path, .. }
+            | Error::InvalidPath { path, .. }
+            | Error::NoMountPoint { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Returns the underlying OS error, if any.
+    pub fn os_error(&self) -> Option<&io::Error> {
+        match self {
+            Error::NotFound { source, .. }
+            | Error::PermissionDenied { source, .. }
+            | Error::InvalidPath { source, .. }
+            | Error::VolumeEnumeration { source }
+            | Error::Metadata { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+
+    /// Returns the [`io::ErrorKind`] this error corresponds to.
+    pub fn kind(&self) -> io::ErrorKind {
+        match self {
+            Error::NotFound { .. } | Error::NoMountPoint { .. } => io::ErrorKind::NotFound,
+            Error::PermissionDenied { .. } => io::ErrorKind::PermissionDenied,
+            Error::InvalidPath { source, .. }
+            | Error::VolumeEnumeration { source }
+            | Error::Metadata { source, .. } => source.kind(),
+        }
+    }
+}
+
+impl Clone for Error {
+    /// I/O 错误本身不可克隆：保留原始 OS 错误码，否则按类型和消息重建
+    fn clone(&self) -> Self {
+        fn clone_io(e: &io::Error) -> io::Error {
+            match e.raw_os_error() {
+                Some(code) => io::Error::from_raw_os_error(code),
+                None => io::Error::new(e.kind(), e.to_string()),
+            }
+        }
+
+        match self {
+            Error::NotFound { path, source } => Error::NotFound { path: path.clone(), source: clone_io(source) },
+            Error::PermissionDenied { path, source } => {
+                Error::PermissionDenied { path: path.clone(), source: clone_io(source) }
+            }
+            Error::InvalidPath { path, source } => Error::InvalidPath { path: path.clone(), source: clone_io(source) },
+            Error::VolumeEnumeration { source } => Error::VolumeEnumeration { source: clone_io(source) },
+            Error::NoMountPoint { path, mount_point } => {
+                Error::NoMountPoint { path: path.clone(), mount_point: mount_point.clone() }
+            }
+            Error::Metadata { volume, source } => Error::Metadata { volume: volume.clone(), source: clone_io(source) },
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotFound { path, source } => {
+                write!(f, "Path `{}` not found: {}", path.display(), source)
+            }
+            Error::PermissionDenied { path, source } => {
+                write!(f, "Access to `{}` denied: {}", path.display(), source)
+            }
+            Error::InvalidPath { path, source } => {
+                write!(f, "Failed to resolve path `{}`: {}", path.display(), source)
+            }
+            Error::VolumeEnumeration { source } => write!(f, "Failed to enumerate volumes: {}", source),
+            Error::NoMountPoint { path, mount_point } => {
+                write!(f, "No volume found for `{}` (mount point `{}`)", path.display(), mount_point.display())
+            }
+            Error::Metadata { volume, source } => {
+                write!(f, "Failed to query metadata of volume `{}`: {}", volume, source)
+            }
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.os_error().map(|e| e as _)
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(error: Error) -> Self {
+        io::Error::new(error.kind(), error)
+    }
+}
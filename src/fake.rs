@@ -0,0 +1,495 @@
+/*
+ * Copyright 2025 爱佐 (Ayrzo)
+ *
+ * This file is part of cargo crate samevol (https://crates.io/crates/samevol),
+ * which licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! 内存中的可编程卷后端，用于在任意平台上声明挂载布局进行测试
+
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use crate::{DevicePath, VolumeBackend, VolumeInfo, VolumeMetadata};
+use crate::matching::PathMatcher;
+use crate::winpath::{self, into_os_string};
+
+/// 路径在内部以 `OsStr` 的编码字节表示，从而无损支持非 Unicode 路径
+type Bytes = Vec<u8>;
+
+/// Path syntax emulated by a [`FakeBackend`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PathStyle {
+    /// `C:\dir\file`，分隔符为 `\`（`/` 会被转换）
+    Windows,
+    /// `/dir/file`，分隔符为 `/`
+    Unix,
+}
+
+impl PathStyle {
+    fn separator(self) -> u8 {
+        match self {
+            PathStyle::Windows => b'\\',
+            PathStyle::Unix => b'/',
+        }
+    }
+
+    fn path_matcher(self) -> PathMatcher {
+        match self {
+            PathStyle::Windows => PathMatcher::windows(),
+            PathStyle::Unix => PathMatcher::unix(),
+        }
+    }
+}
+
+/// 可变的后端状态
+#[derive(Debug)]
+struct FakeState {
+    style: PathStyle,
+    /// 比较挂载点所用的匹配器，默认由路径风格决定
+    matcher: PathMatcher,
+    volumes: Vec<(String, Vec<Bytes>)>,
+    current_dir: Bytes,
+    enumeration_error: Option<io::ErrorKind>,
+    path_errors: HashMap<Bytes, io::ErrorKind>,
+    /// 链接路径 -> 目标完整路径
+    links: Vec<(Bytes, Bytes)>,
+    /// 规范化设备路径 -> 卷元数据
+    metadata: HashMap<String, VolumeMetadata>,
+    /// 规范化设备路径 -> 块设备标识
+    block_devices: HashMap<String, String>,
+    /// 规范化设备路径 -> 物理磁盘标识
+    physical_disks: HashMap<String, Vec<String>>,
+}
+
+/// An in-memory, scriptable [`VolumeBackend`].
+///
+/// Mount layouts are declared up front with the `with_*` methods, and can be changed
+/// later through a shared reference (wrap the backend in an [`Arc`](std::sync::Arc) to
+/// keep a handle after installing it), e.g. to simulate a drive being mounted between
+/// two refreshes. Failures can be injected for enumeration and for individual paths.
+///
+/// Path handling is purely lexical: relative paths are joined to the configured current
+/// directory, and `.` and `..` components are folded. Windows-style backends follow the
+/// rules of `GetFullPathNameW`, see [`winpath`](crate::winpath). Nothing touches the file system,
+/// so the same layout behaves identically on every operating system. Paths that are not
+/// valid Unicode are supported and round-trip unchanged.
+///
+/// Renames follow the declared style as well: Windows-style backends allow renaming
+/// between any two mount points of a volume, Unix-style backends only within a single
+/// mount point (see [`VolumeBackend::renames_across_mount_points`]).
+///
+/// # Example
+/// ```rust
+/// use std::ffi::OsStr;
+/// use std::path::Path;
+/// use samevol::{FakeBackend, VolumeBackend};
+///
+/// let backend = FakeBackend::windows()
+///     .with_volume(r"\\?\Volume{11111111-1111-1111-1111-111111111111}\", [r"C:\"])
+///     .with_volume(r"\\?\Volume{22222222-2222-2222-2222-222222222222}\", [r"C:\mnt\vhd"])
+///     .with_current_dir(r"C:\Users");
+///
+/// let full_path = backend.full_path(Path::new(r"..\mnt\vhd\file.txt")).unwrap();
+/// assert_eq!(full_path.as_os_str(), r"C:\mnt\vhd\file.txt");
+/// assert_eq!(backend.volume_mount_point(&full_path).unwrap(), OsStr::new(r"C:\mnt\vhd\"));
+/// ```
+#[derive(Debug)]
+pub struct FakeBackend {
+    state: Mutex<FakeState>,
+}
+
+impl FakeBackend {
+    /// Creates an empty backend using Windows path syntax, with `C:\` as the current
+    /// directory.
+    pub fn windows() -> Self {
+        Self::with_style(PathStyle::Windows, b"C:\\")
+    }
+
+    /// Creates an empty backend using Unix path syntax, with `/` as the current directory.
+    pub fn unix() -> Self {
+        Self::with_style(PathStyle::Unix, b"/")
+    }
+
+    /// 创建路径风格与匹配器一致的空后端
+    pub(crate) fn with_path_matcher(matcher: PathMatcher) -> Self {
+        let backend = if matcher.is_windows() { Self::windows() } else { Self::unix() };
+        backend.lock().matcher = matcher;
+        backend
+    }
+
+    fn with_style(style: PathStyle, current_dir: &[u8]) -> Self {
+        FakeBackend {
+            state: Mutex::new(FakeState {
+                style,
+                matcher: style.path_matcher(),
+                volumes: Vec::new(),
+                current_dir: current_dir.to_vec(),
+                enumeration_error: None,
+                path_errors: HashMap::new(),
+                links: Vec::new(),
+                metadata: HashMap::new(),
+                block_devices: HashMap::new(),
+                physical_disks: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Declares a volume with the given device path and mount points.
+    pub fn with_volume<I, P>(self, device_path: &str, mount_points: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        for mount_point in mount_points {
+            self.mount(device_path, mount_point);
+        }
+        // 没有挂载点的卷同样需要出现在枚举结果中
+        self.lock().volume_entry(device_path);
+        self
+    }
+
+    /// Sets the directory relative paths are resolved against.
+    pub fn with_current_dir<P: AsRef<Path>>(self, dir: P) -> Self {
+        self.set_current_dir(dir);
+        self
+    }
+
+    /// Declares the metadata reported for the volume `device_path`.
+    ///
+    /// Volumes without declared metadata report an [`io::ErrorKind::Unsupported`] error.
+    pub fn with_metadata(self, device_path: &str, metadata: VolumeMetadata) -> Self {
+        self.set_metadata(device_path, metadata);
+        self
+    }
+
+    /// Declares the block device backing the volume `device_path`, e.g. the partition
+    /// holding several btrfs subvolumes.
+    ///
+    /// Volumes without a declared block device are their own block device.
+    pub fn with_block_device(self, device_path: &str, block_device: &str) -> Self {
+        self.lock().block_devices.insert(canonical_device_path(device_path), block_device.to_owned());
+        self
+    }
+
+    /// Declares the physical disks backing the volume `device_path`.
+    ///
+    /// Volumes without declared disks report an [`io::ErrorKind::Unsupported`] error.
+    pub fn with_physical_disks<I, S>(self, device_path: &str, disks: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let disks = disks.into_iter().map(Into::into).collect();
+        self.lock().physical_disks.insert(canonical_device_path(device_path), disks);
+        self
+    }
+
+    /// Declares `path` as a symbolic link or junction to the full path `target`.
+    ///
+    /// Mount point queries follow links like `GetVolumePathNameW`, so `path` and
+    /// everything below it resolve to the volume of `target`.
+    pub fn with_link<P: AsRef<Path>, Q: AsRef<Path>>(self, path: P, target: Q) -> Self {
+        let mut state = self.lock();
+        let path = state.normalize_separators(path.as_ref());
+        let target = state.normalize_separators(target.as_ref());
+        state.links.push((path, target));
+        drop(state);
+        self
+    }
+
+    /// Makes every query for `path` fail with an error of the given kind.
+    pub fn with_path_error<P: AsRef<Path>>(self, path: P, kind: io::ErrorKind) -> Self {
+        self.fail_path(path, kind);
+        self
+    }
+
+    /// Mounts the volume `device_path` at `mount_point`, creating the volume if needed.
+    ///
+    /// A volume previously mounted at the same location is unmounted first.
+    pub fn mount<P: AsRef<Path>>(&self, device_path: &str, mount_point: P) {
+        let mut state = self.lock();
+        let mount_point = state.normalize_mount_point(mount_point.as_ref());
+        state.remove_mount_point(&mount_point);
+        state.volume_entry(device_path).push(mount_point);
+    }
+
+    /// Unmounts whatever is mounted at `mount_point`.
+    ///
+    /// Returns `false` if nothing was mounted there. The volume itself stays known.
+    pub fn unmount<P: AsRef<Path>>(&self, mount_point: P) -> bool {
+        let mut state = self.lock();
+        let mount_point = state.normalize_mount_point(mount_point.as_ref());
+        state.remove_mount_point(&mount_point)
+    }
+
+    /// Removes a volume and all of its mount points.
+    ///
+    /// Returns `false` if the volume was unknown.
+    pub fn remove_volume(&self, device_path: &str) -> bool {
+        let mut state = self.lock();
+        let device_path = canonical_device_path(device_path);
+        let before = state.volumes.len();
+        state.volumes.retain(|(id, _)| canonical_device_path(id) != device_path);
+        state.metadata.remove(&device_path);
+        state.block_devices.remove(&device_path);
+        state.physical_disks.remove(&device_path);
+        state.volumes.len() != before
+    }
+
+    /// Changes the metadata reported for the volume `device_path`, e.g. to simulate the
+    /// free space shrinking.
+    pub fn set_metadata(&self, device_path: &str, metadata: VolumeMetadata) {
+        self.lock().metadata.insert(canonical_device_path(device_path), metadata);
+    }
+
+    /// Changes the directory relative paths are resolved against.
+    pub fn set_current_dir<P: AsRef<Path>>(&self, dir: P) {
+        let mut state = self.lock();
+        state.current_dir = state.normalize_separators(dir.as_ref());
+    }
+
+    /// Makes [`volumes`](VolumeBackend::volumes) fail with an error of the given kind.
+    pub fn fail_enumeration(&self, kind: io::ErrorKind) {
+        self.lock().enumeration_error = Some(kind);
+    }
+
+    /// Makes every query for `path` fail with an error of the given kind.
+    ///
+    /// The path is matched both as given and after expansion to a full path.
+    pub fn fail_path<P: AsRef<Path>>(&self, path: P, kind: io::ErrorKind) {
+        let mut state = self.lock();
+        let path = state.normalize_separators(path.as_ref());
+        state.path_errors.insert(path, kind);
+    }
+
+    /// Removes all failures injected with [`fail_enumeration`](Self::fail_enumeration)
+    /// and [`fail_path`](Self::fail_path).
+    pub fn clear_failures(&self) {
+        let mut state = self.lock();
+        state.enumeration_error = None;
+        state.path_errors.clear();
+    }
+
+    /// 获取状态锁（测试代码中的 panic 不应使后端永久不可用）
+    fn lock(&self) -> MutexGuard<'_, FakeState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl VolumeBackend for FakeBackend {
+    fn volumes(&self) -> io::Result<Vec<(String, Vec<OsString>)>> {
+        let state = self.lock();
+        if let Some(kind) = state.enumeration_error {
+            return Err(io::Error::new(kind, "injected volume enumeration failure"));
+        }
+
+        Ok(state
+            .volumes
+            .iter()
+            .map(|(id, mount_points)| (id.clone(), mount_points.iter().cloned().map(into_os_string).collect()))
+            .collect())
+    }
+
+    fn full_path(&self, path: &Path) -> io::Result<PathBuf> {
+        let state = self.lock();
+        let path = state.normalize_separators(path);
+        state.check_path(&path)?;
+        if path.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "empty path"));
+        }
+
+        let full_path = state.full_path(&path)?;
+        state.check_path(&full_path)?;
+        Ok(PathBuf::from(into_os_string(full_path)))
+    }
+
+    fn volume_mount_point(&self, full_path: &Path) -> io::Result<OsString> {
+        let state = self.lock();
+        state.check_path(full_path.as_os_str().as_encoded_bytes())?;
+        let full_path = &state.follow_links(full_path);
+
+        // 与 `GetVolumePathNameW` 一致，Windows 风格下不区分大小写
+        let mount_points = state
+            .volumes
+            .iter()
+            .flat_map(|(_, mount_points)| mount_points)
+            .map(|mount_point| PathBuf::from(into_os_string(mount_point.clone())));
+        state
+            .matcher
+            .longest_match(full_path, mount_points)
+            .map(PathBuf::into_os_string)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no volume is mounted for path"))
+    }
+
+    fn is_link(&self, full_path: &Path) -> bool {
+        let state = self.lock();
+        let key = state.matcher.key(full_path);
+        state.links.iter().any(|(link, _)| state.matcher.key(into_os_string(link.clone())) == key)
+    }
+
+    fn path_matcher(&self) -> PathMatcher {
+        self.lock().matcher
+    }
+
+    fn renames_across_mount_points(&self) -> bool {
+        self.lock().style == PathStyle::Windows
+    }
+
+    fn block_device(&self, volume: &VolumeInfo) -> io::Result<String> {
+        let id = volume.id().as_str();
+        Ok(self.lock().block_devices.get(id).map_or(id, String::as_str).to_owned())
+    }
+
+    fn physical_disks(&self, volume: &VolumeInfo) -> io::Result<Vec<String>> {
+        self.lock()
+            .physical_disks
+            .get(volume.id().as_str())
+            .cloned()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "no physical disks declared for volume"))
+    }
+
+    fn volume_metadata(&self, volume: &VolumeInfo) -> io::Result<VolumeMetadata> {
+        self.lock()
+            .metadata
+            .get(volume.id().as_str())
+            .cloned()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "no metadata declared for volume"))
+    }
+}
+
+/// 与解析器一致地规范化设备路径，无法解析时原样使用
+fn canonical_device_path(device_path: &str) -> String {
+    DevicePath::parse(device_path).map(String::from).unwrap_or_else(|_| device_path.to_owned())
+}
+
+impl FakeState {
+    /// 获取（必要时创建）卷的挂载点列表
+    fn volume_entry(&mut self, device_path: &str) -> &mut Vec<Bytes> {
+        let index = match self.volumes.iter().position(|(id, _)| id == device_path) {
+            Some(index) => index,
+            None => {
+                self.volumes.push((device_path.to_owned(), Vec::new()));
+                self.volumes.len() - 1
+            }
+        };
+        &mut self.volumes[index].1
+    }
+
+    /// 从所有卷中移除指定挂载点
+    fn remove_mount_point(&mut self, mount_point: &[u8]) -> bool {
+        let mut removed = false;
+        for (_, mount_points) in &mut self.volumes {
+            let before = mount_points.len();
+            mount_points.retain(|m| m != mount_point);
+            removed |= mount_points.len() != before;
+        }
+        removed
+    }
+
+    /// 将路径中的链接替换为其目标，直到不再包含链接（层数有限，避免循环链接）
+    fn follow_links(&self, full_path: &Path) -> PathBuf {
+        const MAX_LINK_DEPTH: usize = 40;
+
+        let separator = self.style.separator();
+        let components = |path: &[u8]| path.split(|&b| b == separator).filter(|c| !c.is_empty()).count();
+
+        let mut path = self.normalize_separators(full_path);
+        for _ in 0..MAX_LINK_DEPTH {
+            let links = self.links.iter().map(|(link, target)| (into_os_string(link.clone()), target));
+            let Some((link, target)) = links
+                .filter(|(link, _)| self.matcher.contains(link, into_os_string(path.clone())))
+                .max_by_key(|(link, _)| self.matcher.key(link).len())
+            else {
+                break;
+            };
+
+            // 以目标替换链接对应的前几个组件
+            let mut resolved = target.clone();
+            let depth = components(link.as_encoded_bytes());
+            for component in path.split(|&b| b == separator).filter(|c| !c.is_empty()).skip(depth) {
+                if resolved.last() != Some(&separator) {
+                    resolved.push(separator);
+                }
+                resolved.extend_from_slice(component);
+            }
+            path = resolved;
+        }
+        PathBuf::from(into_os_string(path))
+    }
+
+    /// 检查是否为该路径注入了错误
+    fn check_path(&self, path: &[u8]) -> io::Result<()> {
+        match self.path_errors.get(path) {
+            Some(&kind) => Err(io::Error::new(
+                kind,
+                format!("injected failure for `{}`", String::from_utf8_lossy(path)),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn normalize_separators(&self, path: &Path) -> Bytes {
+        let bytes = path.as_os_str().as_encoded_bytes();
+        match self.style {
+            PathStyle::Windows => bytes.iter().map(|&b| if b == b'/' { b'\\' } else { b }).collect(),
+            PathStyle::Unix => bytes.to_vec(),
+        }
+    }
+
+    fn normalize_mount_point(&self, mount_point: &Path) -> Bytes {
+        self.with_trailing_separator(self.normalize_separators(mount_point))
+    }
+
+    fn with_trailing_separator(&self, mut path: Bytes) -> Bytes {
+        let separator = self.style.separator();
+        if path.last() != Some(&separator) {
+            path.push(separator);
+        }
+        path
+    }
+
+    /// 按字面拼接当前目录并折叠 `.` 和 `..`
+    fn full_path(&self, path: &[u8]) -> io::Result<Bytes> {
+        // Windows 路径按 `GetFullPathNameW` 的规则处理
+        if self.style == PathStyle::Windows {
+            return winpath::full_path(path, &self.current_dir, |_| None);
+        }
+
+        let joined = match path.starts_with(b"/") {
+            true => path.to_vec(),
+            false => [&self.current_dir[..], b"/", path].concat(),
+        };
+
+        let mut components: Vec<&[u8]> = Vec::new();
+        for component in joined.split(|&b| b == b'/') {
+            match component {
+                b"" | b"." => {}
+                b".." => {
+                    components.pop();
+                }
+                name => components.push(name),
+            }
+        }
+
+        let mut full_path = b"/".to_vec();
+        full_path.extend_from_slice(&components.join(&b'/'));
+        if joined.last() == Some(&b'/') && !components.is_empty() {
+            full_path.push(b'/');
+        }
+        Ok(full_path)
+    }
+}
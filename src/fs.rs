@@ -0,0 +1,735 @@
+/*
+ * Copyright 2025 爱佐 (Ayrzo)
+ *
+ * This file is part of cargo crate samevol (https://crates.io/crates/samevol),
+ * which licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Moving files and directories across volumes, and writing files atomically.
+//!
+//! [`move_path`] renames when the source and destination are on the same mount, and
+//! otherwise copies the data with its metadata, syncs it to disk and only then deletes the
+//! source. The decision is made with [`can_rename`](crate::can_rename), and a rename that
+//! still fails with a cross-device error falls back to copying.
+//!
+//! [`atomic_file`] creates a temporary file on the same mount as a destination, so that
+//! it can replace the destination with a single rename once written.
+//!
+//! # Example
+//! ```rust,no_run
+//! use samevol::fs::{move_path, MoveMethod, MoveOptions};
+//!
+//! // Simple move
+//! if move_path("/home/user/video.mkv", "/mnt/archive/video.mkv")? == MoveMethod::Copied {
+//!     println!("Moved across volumes");
+//! }
+//!
+//! // With progress reporting
+//! MoveOptions::new()
+//!     .progress(|progress| println!("{}/{} bytes", progress.bytes_copied(), progress.total_bytes()))
+//!     .move_path("/home/user/photos", "/mnt/archive/photos")?;
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use std::fmt;
+use std::fs::{self, File, Metadata, OpenOptions};
+use std::io::{self, Read as _, Write as _};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::Resolver;
+
+/// 复制文件时的缓冲区大小
+const COPY_BUFFER_LEN: usize = 256 * 1024;
+
+/// 进度回调
+type ProgressFn<'a> = dyn FnMut(&Progress<'_>) + 'a;
+/// 临时文件名冲突时的最大重试次数
+const TEMP_NAME_ATTEMPTS: u32 = 64;
+
+/// 进程内的临时文件序号，与进程 ID 一起保证文件名唯一
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// How [`move_path`] moved a path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum MoveMethod {
+    /// The path was renamed in place.
+    Renamed,
+    /// The path was copied to the destination, then removed from the source.
+    Copied,
+}
+
+/// Progress of a copy, passed to the callback set with [`MoveOptions::progress`].
+#[derive(Debug, Clone, Copy)]
+pub struct Progress<'a> {
+    path: &'a Path,
+    bytes_copied: u64,
+    total_bytes: u64,
+    files_copied: u64,
+    total_files: u64,
+}
+
+impl Progress<'_> {
+    /// Returns the source path of the file being copied.
+    pub fn path(&self) -> &Path {
+        self.path
+    }
+
+    /// Returns the number of bytes copied so far, over all files.
+    pub fn bytes_copied(&self) -> u64 {
+        self.bytes_copied
+    }
+
+    /// Returns the total number of bytes to copy, over all files.
+    pub fn total_bytes(&self) -> u64 {
+        self.total_bytes
+    }
+
+    /// Returns the number of files (including symbolic links) copied completely so far.
+    pub fn files_copied(&self) -> u64 {
+        self.files_copied
+    }
+
+    /// Returns the total number of files (including symbolic links) to copy.
+    pub fn total_files(&self) -> u64 {
+        self.total_files
+    }
+}
+
+/// Options for moving a path, see [`move_path`].
+///
+/// # Example
+/// ```rust,no_run
+/// use samevol::fs::MoveOptions;
+/// use samevol::Resolver;
+///
+/// let resolver = Resolver::new();
+/// let method = MoveOptions::new()
+///     .resolver(&resolver)
+///     .progress(|progress| eprintln!("{}", progress.path().display()))
+///     .move_path(r"C:\Users\Public\big.iso", r"E:\big.iso")?;
+/// println!("{:?}", method);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Default)]
+pub struct MoveOptions<'a> {
+    resolver: Option<&'a Resolver>,
+    progress: Option<Box<ProgressFn<'a>>>,
+}
+
+impl fmt::Debug for MoveOptions<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MoveOptions")
+            .field("resolver", &self.resolver)
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
+}
+
+impl<'a> MoveOptions<'a> {
+    /// Creates options using the [default resolver](crate::default_resolver) and no
+    /// progress callback.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the resolver used to decide whether a rename is possible.
+    pub fn resolver(mut self, resolver: &'a Resolver) -> Self {
+        self.resolver = Some(resolver);
+        self
+    }
+
+    /// Sets a callback invoked while copying: after every chunk of data written and after
+    /// every file completed.
+    ///
+    /// The callback is not invoked when the path is renamed.
+    pub fn progress<F: FnMut(&Progress<'_>) + 'a>(mut self, progress: F) -> Self {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    /// Moves `src` to `dst` with these options.
+    ///
+    /// See [`move_path`] for details.
+    pub fn move_path<P: AsRef<Path>, Q: AsRef<Path>>(&mut self, src: P, dst: Q) -> io::Result<MoveMethod> {
+        let (src, dst) = (src.as_ref(), dst.as_ref());
+
+        // 与 rename 不同，目标已存在时一律拒绝，避免复制路径覆盖数据
+        let metadata = fs::symlink_metadata(src)?;
+        match fs::symlink_metadata(dst) {
+            Ok(_) => return Err(io::Error::new(io::ErrorKind::AlreadyExists, "destination already exists")),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+
+        // 符号链接本身位于其所在目录的挂载上，判断时不能跟随链接到目标所在的挂载
+        let src_entry = match metadata.file_type().is_symlink() {
+            true => src.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new(".")),
+            false => src,
+        };
+
+        // 无法判断时同样先尝试重命名，跨设备失败后再复制
+        let can_rename = match self.resolver {
+            Some(resolver) => resolver.try_can_rename(src_entry, dst),
+            None => crate::try_can_rename(src_entry, dst),
+        };
+        if !matches!(can_rename, Ok(false)) {
+            match fs::rename(src, dst) {
+                Ok(()) => return Ok(MoveMethod::Renamed),
+                Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        self.copy_and_remove(src, dst, &metadata)?;
+        Ok(MoveMethod::Copied)
+    }
+
+    /// 复制到目标并落盘，失败时回滚已创建的内容；成功后才删除源路径
+    fn copy_and_remove(&mut self, src: &Path, dst: &Path, metadata: &Metadata) -> io::Result<()> {
+        let (total_files, total_bytes) = measure(src, metadata)?;
+        let mut copier = Copier {
+            progress: self.progress.as_deref_mut(),
+            buffer: vec![0; COPY_BUFFER_LEN],
+            bytes_copied: 0,
+            total_bytes,
+            files_copied: 0,
+            total_files,
+            created: Vec::new(),
+            directories: Vec::new(),
+        };
+
+        let result = copier
+            .copy(src, dst, metadata)
+            .and_then(|()| copier.finish_directories())
+            .and_then(|()| sync_parent(dst));
+        if let Err(e) = result {
+            copier.rollback();
+            return Err(e);
+        }
+
+        // 此时目标已完整落盘；删除源路径失败时保留两份数据，不做回滚
+        if metadata.is_dir() { fs::remove_dir_all(src) } else { fs::remove_file(src) }
+    }
+}
+
+/// Moves a file, directory or symbolic link from `src` to `dst`.
+///
+/// If both paths are on the same mount (see [`can_rename`](crate::can_rename); for a
+/// symbolic link, the mount of the directory holding it), the path is renamed, which is atomic and does not copy any data. Otherwise it is copied:
+/// 1. Directories are copied recursively, symbolic links are recreated (not followed)
+/// 2. Permissions and access/modification times are copied along with the data
+/// 3. Every file and directory is synced to disk
+/// 4. Only then the source is deleted
+///
+/// If copying fails, everything created at `dst` so far is removed again and the source
+/// is left untouched. If deleting the source fails afterwards, the error is returned but
+/// the complete copy at `dst` is kept, so that no data is lost.
+///
+/// Unlike [`std::fs::rename`], the move fails if `dst` already exists. Ownership,
+/// extended attributes and alternate data streams are not copied. Special files (sockets,
+/// FIFOs, device nodes) cannot be copied.
+///
+/// Use [`MoveOptions`] for progress reporting or to use another [`Resolver`].
+///
+/// # Arguments
+/// * `src` - The path to move
+/// * `dst` - The new path, which must not exist yet
+///
+/// # Returns
+/// - `Ok(MoveMethod)`: How the path was moved
+/// - `Err(io::Error)`: Error encountered while renaming, copying or deleting
+///
+/// # Example
+/// ```rust,no_run
+/// use samevol::fs::move_path;
+///
+/// move_path(r"C:\Users\Public\Downloads\setup.exe", r"D:\Installers\setup.exe")?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn move_path<P: AsRef<Path>, Q: AsRef<Path>>(src: P, dst: Q) -> io::Result<MoveMethod> {
+    MoveOptions::new().move_path(src, dst)
+}
+
+/// 复制过程中创建的条目，用于回滚
+enum Created {
+    File(PathBuf),
+    Directory(PathBuf),
+    Symlink(PathBuf),
+}
+
+/// 递归复制的状态
+struct Copier<'c, 'a> {
+    progress: Option<&'c mut ProgressFn<'a>>,
+    buffer: Vec<u8>,
+    bytes_copied: u64,
+    total_bytes: u64,
+    files_copied: u64,
+    total_files: u64,
+    /// 按创建顺序记录
+    created: Vec<Created>,
+    /// 目录的权限和时间在其内容复制完成后才设置，否则只读目录无法写入
+    directories: Vec<(PathBuf, Metadata)>,
+}
+
+impl Copier<'_, '_> {
+    fn copy(&mut self, src: &Path, dst: &Path, metadata: &Metadata) -> io::Result<()> {
+        let file_type = metadata.file_type();
+        if file_type.is_symlink() {
+            self.copy_symlink(src, dst, metadata)
+        } else if file_type.is_dir() {
+            self.copy_dir(src, dst, metadata)
+        } else if file_type.is_file() {
+            self.copy_file(src, dst, metadata)
+        } else {
+            Err(io::Error::new(io::ErrorKind::Unsupported, format!("cannot copy special file `{}`", src.display())))
+        }
+    }
+
+    fn copy_dir(&mut self, src: &Path, dst: &Path, metadata: &Metadata) -> io::Result<()> {
+        fs::create_dir(dst)?;
+        self.created.push(Created::Directory(dst.to_path_buf()));
+
+        for entry in fs::read_dir(src)? {
+            let entry = entry?;
+            // 不跟随符号链接
+            let metadata = entry.metadata()?;
+            self.copy(&entry.path(), &dst.join(entry.file_name()), &metadata)?;
+        }
+
+        self.directories.push((dst.to_path_buf(), metadata.clone()));
+        Ok(())
+    }
+
+    fn copy_file(&mut self, src: &Path, dst: &Path, metadata: &Metadata) -> io::Result<()> {
+        let mut reader = File::open(src)?;
+        let mut writer = OpenOptions::new().write(true).create_new(true).open(dst)?;
+        self.created.push(Created::File(dst.to_path_buf()));
+
+        loop {
+            let len = match reader.read(&mut self.buffer) {
+                Ok(0) => break,
+                Ok(len) => len,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            writer.write_all(&self.buffer[..len])?;
+            self.bytes_copied += len as u64;
+            self.report(src);
+        }
+
+        // 元数据在落盘前全部设置，使其与内容一起持久化；权限通过已打开的句柄设置，只读属性不影响前面的写入
+        writer.set_times(file_times(metadata))?;
+        writer.set_permissions(metadata.permissions())?;
+        writer.sync_all()?;
+
+        self.files_copied += 1;
+        self.report(src);
+        Ok(())
+    }
+
+    fn copy_symlink(&mut self, src: &Path, dst: &Path, metadata: &Metadata) -> io::Result<()> {
+        let target = fs::read_link(src)?;
+        create_symlink(&target, dst, metadata)?;
+        self.created.push(Created::Symlink(dst.to_path_buf()));
+
+        self.files_copied += 1;
+        self.report(src);
+        Ok(())
+    }
+
+    /// 由内而外设置目录的时间和权限并落盘
+    fn finish_directories(&mut self) -> io::Result<()> {
+        for (dir, metadata) in self.directories.iter().rev() {
+            sync_dir(dir)?;
+            set_dir_times(dir, metadata)?;
+            fs::set_permissions(dir, metadata.permissions())?;
+        }
+        Ok(())
+    }
+
+    fn report(&mut self, path: &Path) {
+        if let Some(progress) = &mut self.progress {
+            progress(&Progress {
+                path,
+                bytes_copied: self.bytes_copied,
+                total_bytes: self.total_bytes,
+                files_copied: self.files_copied,
+                total_files: self.total_files,
+            });
+        }
+    }
+
+    /// 按创建的逆序删除已创建的条目（尽力而为）
+    fn rollback(&mut self) {
+        for created in self.created.drain(..).rev() {
+            let _ = match created {
+                Created::File(path) => fs::remove_file(path),
+                Created::Directory(path) => fs::remove_dir(path),
+                Created::Symlink(path) => fs::remove_file(&path).or_else(|_| fs::remove_dir(&path)),
+            };
+        }
+    }
+}
+
+/// 统计需要复制的文件数（含符号链接）和字节数
+fn measure(path: &Path, metadata: &Metadata) -> io::Result<(u64, u64)> {
+    if !metadata.is_dir() {
+        let len = if metadata.is_file() { metadata.len() } else { 0 };
+        return Ok((1, len));
+    }
+
+    let (mut files, mut bytes) = (0, 0);
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let (entry_files, entry_bytes) = measure(&entry.path(), &entry.metadata()?)?;
+        files += entry_files;
+        bytes += entry_bytes;
+    }
+    Ok((files, bytes))
+}
+
+/// 源文件的访问时间和修改时间（平台不支持时忽略）
+fn file_times(metadata: &Metadata) -> fs::FileTimes {
+    let mut times = fs::FileTimes::new();
+    if let Ok(accessed) = metadata.accessed() {
+        times = times.set_accessed(accessed);
+    }
+    if let Ok(modified) = metadata.modified() {
+        times = times.set_modified(modified);
+    }
+    times
+}
+
+/// 落盘目标所在的目录，使新建的目录项持久化
+fn sync_parent(path: &Path) -> io::Result<()> {
+    match path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        Some(parent) => sync_dir(parent),
+        None => sync_dir(Path::new(".")),
+    }
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &Path, link: &Path, _metadata: &Metadata) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn create_symlink(target: &Path, link: &Path, metadata: &Metadata) -> io::Result<()> {
+    use std::os::windows::fs::FileTypeExt as _;
+
+    if metadata.file_type().is_symlink_dir() {
+        std::os::windows::fs::symlink_dir(target, link)
+    } else {
+        std::os::windows::fs::symlink_file(target, link)
+    }
+}
+
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> io::Result<()> {
+    File::open(dir)?.sync_all()
+}
+
+/// Windows 上目录项随文件系统日志持久化，无法也无需单独落盘
+#[cfg(windows)]
+fn sync_dir(_dir: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(unix)]
+fn set_dir_times(dir: &Path, metadata: &Metadata) -> io::Result<()> {
+    File::open(dir)?.set_times(file_times(metadata))
+}
+
+#[cfg(windows)]
+fn set_dir_times(dir: &Path, metadata: &Metadata) -> io::Result<()> {
+    use std::os::windows::fs::OpenOptionsExt as _;
+
+    /// 打开目录句柄所需的标志
+    const FILE_FLAG_BACKUP_SEMANTICS: u32 = 0x0200_0000;
+    /// 仅修改属性所需的访问权限
+    const FILE_WRITE_ATTRIBUTES: u32 = 0x0100;
+
+    OpenOptions::new()
+        .access_mode(FILE_WRITE_ATTRIBUTES)
+        .custom_flags(FILE_FLAG_BACKUP_SEMANTICS)
+        .open(dir)?
+        .set_times(file_times(metadata))
+}
+
+/// Options for creating an [`AtomicFile`], see [`atomic_file`].
+///
+/// # Example
+/// ```rust,no_run
+/// use std::io::Write as _;
+/// use samevol::fs::AtomicFileOptions;
+///
+/// let mut file = AtomicFileOptions::new()
+///     .fallback_dir("/var/tmp/myapp")
+///     .create("/srv/data/config.toml")?;
+/// file.write_all(b"answer = 42\n")?;
+/// file.commit()?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AtomicFileOptions<'a> {
+    resolver: Option<&'a Resolver>,
+    volume_cache_dir: Option<PathBuf>,
+    fallback_dirs: Vec<PathBuf>,
+}
+
+impl<'a> AtomicFileOptions<'a> {
+    /// Creates options using the [default resolver](crate::default_resolver), without
+    /// per-volume cache directory or fallback directory.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the resolver used to verify that a directory is on the same mount as the
+    /// destination.
+    pub fn resolver(mut self, resolver: &'a Resolver) -> Self {
+        self.resolver = Some(resolver);
+        self
+    }
+
+    /// Enables a per-volume cache directory with the given name (e.g. `.myapp-tmp`),
+    /// created on demand at the root of the mount holding the destination.
+    ///
+    /// The directory is left in place afterwards, so it is only used when enabled
+    /// explicitly.
+    pub fn volume_cache_dir<P: Into<PathBuf>>(mut self, name: P) -> Self {
+        self.volume_cache_dir = Some(name.into());
+        self
+    }
+
+    /// Disables the per-volume cache directory enabled with
+    /// [`volume_cache_dir`](Self::volume_cache_dir).
+    pub fn no_volume_cache_dir(mut self) -> Self {
+        self.volume_cache_dir = None;
+        self
+    }
+
+    /// Adds a directory to try when neither the destination directory nor the
+    /// per-volume cache directory can be used. Fallbacks are tried in the order added,
+    /// and only used if they are on the same mount as the destination.
+    pub fn fallback_dir<P: Into<PathBuf>>(mut self, dir: P) -> Self {
+        self.fallback_dirs.push(dir.into());
+        self
+    }
+
+    /// Creates a temporary file for atomically writing `dst`.
+    ///
+    /// See [`atomic_file`] for details.
+    pub fn create<P: AsRef<Path>>(&self, dst: P) -> io::Result<AtomicFile> {
+        let dst = dst.as_ref();
+        let file_name = dst
+            .file_name()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "destination has no file name"))?;
+        let dst_dir = match dst.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            Some(parent) => parent,
+            None => Path::new("."),
+        };
+
+        let default_resolver;
+        let resolver = match self.resolver {
+            Some(resolver) => resolver,
+            None => {
+                default_resolver = crate::default_resolver();
+                &default_resolver
+            }
+        };
+
+        // 候选目录：目标所在目录、目标挂载点下的缓存目录、配置的后备目录
+        let mut candidates = vec![(dst_dir.to_path_buf(), false)];
+        if let Some(name) = &self.volume_cache_dir
+            && let Ok(mount_point) = resolver.try_resolve_mount_point(dst)
+        {
+            candidates.push((mount_point.as_path().join(name), true));
+        }
+        candidates.extend(self.fallback_dirs.iter().map(|dir| (dir.clone(), false)));
+
+        let mut last_error = None;
+        for (dir, create_dir) in candidates {
+            if create_dir && let Err(e) = fs::create_dir(&dir) && e.kind() != io::ErrorKind::AlreadyExists {
+                last_error = Some(e);
+                continue;
+            }
+
+            let mut name = file_name.to_owned();
+            name.push(".tmp");
+            // 每个候选目录都须经过验证：目标本身是挂载点（如绑定挂载的文件）时，其所在目录位于另一个挂载上
+            if !resolver.can_rename(dir.join(&name), dst) {
+                last_error = Some(io::Error::new(
+                    io::ErrorKind::CrossesDevices,
+                    format!("{} is not on the same mount as the destination", dir.display()),
+                ));
+                continue;
+            }
+
+            match create_temp_file(&dir, file_name) {
+                Ok((file, path)) => return Ok(AtomicFile { file: Some(file), path, destination: dst.to_path_buf() }),
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        Err(last_error.expect("the destination directory is always a candidate"))
+    }
+}
+
+/// Creates a temporary file for atomically writing `dst`.
+///
+/// The temporary file is created in the first usable directory on the same mount as
+/// `dst` (verified with [`can_rename`](crate::can_rename)), so that
+/// [`commit`](AtomicFile::commit) can replace `dst` with a single rename. Readers of
+/// `dst` see either the old or the new content, never a partially written file. The
+/// directories tried are, in order:
+/// 1. The directory of `dst`
+/// 2. A per-volume cache directory at the root of the mount holding `dst`, if enabled
+///    with [`AtomicFileOptions::volume_cache_dir`]
+/// 3. The fallback directories configured with [`AtomicFileOptions::fallback_dir`]
+///
+/// # Errors
+/// - [`io::ErrorKind::InvalidInput`]: `dst` has no file name
+/// - Otherwise the error of the last directory tried, e.g.
+///   [`io::ErrorKind::CrossesDevices`] for a fallback directory on another mount
+///
+/// # Example
+/// ```rust,no_run
+/// use std::io::Write as _;
+/// use samevol::fs::atomic_file;
+///
+/// let mut file = atomic_file(r"D:\Data\report.csv")?;
+/// file.write_all(b"id,value\n1,42\n")?;
+/// file.commit()?; // Dropping the file without committing discards it
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn atomic_file<P: AsRef<Path>>(dst: P) -> io::Result<AtomicFile> {
+    AtomicFileOptions::new().create(dst)
+}
+
+/// A temporary file that atomically replaces its destination when committed.
+///
+/// Created with [`atomic_file`] or [`AtomicFileOptions::create`]. Write to it through
+/// [`io::Write`] or the underlying [`File`], then call [`commit`](Self::commit). If the
+/// handle is dropped without committing, the temporary file is deleted and the
+/// destination stays untouched.
+#[derive(Debug)]
+pub struct AtomicFile {
+    /// 提交或丢弃时先关闭文件（Windows 上无法重命名或删除已打开的文件）
+    file: Option<File>,
+    path: PathBuf,
+    destination: PathBuf,
+}
+
+impl AtomicFile {
+    /// Returns the path of the temporary file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the path the file will be committed to.
+    pub fn destination(&self) -> &Path {
+        &self.destination
+    }
+
+    /// Returns the underlying temporary file.
+    pub fn as_file(&self) -> &File {
+        self.file.as_ref().expect("file is open until committed or dropped")
+    }
+
+    /// Returns the underlying temporary file mutably, e.g. to set its length or
+    /// permissions.
+    pub fn as_file_mut(&mut self) -> &mut File {
+        self.file.as_mut().expect("file is open until committed or dropped")
+    }
+
+    /// Syncs the temporary file to disk and renames it to the destination, replacing
+    /// any existing file there.
+    ///
+    /// # Errors
+    /// Returns the error of syncing or renaming. The temporary file is deleted in that
+    /// case, and the destination stays untouched.
+    pub fn commit(mut self) -> io::Result<()> {
+        let file = self.file.take().expect("file is open until committed or dropped");
+        let result = file.sync_all().and_then(|()| {
+            drop(file);
+            fs::rename(&self.path, &self.destination)
+        });
+        if let Err(e) = result {
+            let _ = fs::remove_file(&self.path);
+            return Err(e);
+        }
+        // 已重命名，`drop` 中无需再删除
+        self.path = PathBuf::new();
+        sync_parent(&self.destination)
+    }
+
+    /// Deletes the temporary file without touching the destination.
+    ///
+    /// This is what dropping the handle does, but reports errors.
+    pub fn discard(mut self) -> io::Result<()> {
+        drop(self.file.take());
+        let path = std::mem::take(&mut self.path);
+        fs::remove_file(path)
+    }
+}
+
+impl io::Write for AtomicFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.as_file_mut().write(buf)
+    }
+
+    fn write_vectored(&mut self, bufs: &[io::IoSlice<'_>]) -> io::Result<usize> {
+        self.as_file_mut().write_vectored(bufs)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.as_file_mut().flush()
+    }
+}
+
+impl io::Seek for AtomicFile {
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        self.as_file_mut().seek(pos)
+    }
+}
+
+impl Drop for AtomicFile {
+    fn drop(&mut self) {
+        drop(self.file.take());
+        if !self.path.as_os_str().is_empty() {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// 在目录中以唯一的名称新建临时文件
+fn create_temp_file(dir: &Path, file_name: &std::ffi::OsStr) -> io::Result<(File, PathBuf)> {
+    let mut last_error = None;
+    for _ in 0..TEMP_NAME_ATTEMPTS {
+        let counter = TEMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+        let mut name = std::ffi::OsString::from(".");
+        name.push(file_name);
+        name.push(format!(".{:x}-{:x}.tmp", std::process::id(), counter));
+
+        let path = dir.join(name);
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((file, path)),
+            // 崩溃进程遗留的同名文件，换一个序号重试
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => last_error = Some(e),
+            Err(e) => return Err(e),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| io::Error::from(io::ErrorKind::AlreadyExists)))
+}
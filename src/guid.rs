@@ -0,0 +1,256 @@
+/*
+ * Copyright 2025 爱佐 (Ayrzo)
+ *
+ * This file is part of cargo crate samevol (https://crates.io/crates/samevol),
+ * which licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Volume GUID paths and GUIDs.
+//!
+//! Windows names every volume with a path of the form
+//! `\\?\Volume{xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx}\`. For volumes on GPT disks the GUID
+//! is the unique partition GUID of the partition entry, which is stored on disk in a
+//! mixed-endian 16-byte layout: the first three fields are little-endian, the last eight
+//! bytes are stored as is. This module parses and formats both forms without calling
+//! any Windows API, so it can be used (and tested) on any OS.
+//!
+//! # Example
+//! ```rust
+//! use samevol::guid::{Guid, VolumeGuidPath};
+//!
+//! let path: VolumeGuidPath = r"\\?\volume{E8A7F3C2-1234-5678-9ABC-DEF012345678}".parse().unwrap();
+//! assert_eq!(path.to_string(), r"\\?\Volume{e8a7f3c2-1234-5678-9abc-def012345678}\");
+//!
+//! let bytes = path.guid().to_gpt_bytes();
+//! assert_eq!(bytes[..4], [0xc2, 0xf3, 0xa7, 0xe8]);
+//! assert_eq!(Guid::from_gpt_bytes(bytes), path.guid());
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::ParseError;
+
+/// 卷 GUID 路径前缀（`Volume` 部分不区分大小写）
+const PREFIX: &str = r"\\?\Volume{";
+
+/// A GUID, such as the unique partition GUID of a GPT partition entry.
+///
+/// The textual form is `xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx`, formatted with lowercase
+/// hexadecimal digits like Windows does in volume GUID paths.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(into = "String", try_from = "String"))]
+pub struct Guid {
+    data1: u32,
+    data2: u16,
+    data3: u16,
+    data4: [u8; 8],
+}
+
+impl Guid {
+    /// Creates a GUID from its fields, as in the Windows `GUID` structure.
+    pub const fn from_fields(data1: u32, data2: u16, data3: u16, data4: [u8; 8]) -> Self {
+        Guid { data1, data2, data3, data4 }
+    }
+
+    /// Returns the fields of the GUID, as in the Windows `GUID` structure.
+    pub const fn as_fields(&self) -> (u32, u16, u16, &[u8; 8]) {
+        (self.data1, self.data2, self.data3, &self.data4)
+    }
+
+    /// Creates a GUID from its 16-byte mixed-endian form, as stored in GPT partition
+    /// entries and in memory on Windows.
+    ///
+    /// # Arguments
+    /// - `bytes` - The first three fields in little-endian order, followed by the last
+    ///   eight bytes as is
+    pub const fn from_gpt_bytes(bytes: [u8; 16]) -> Self {
+        Guid {
+            data1: u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            data2: u16::from_le_bytes([bytes[4], bytes[5]]),
+            data3: u16::from_le_bytes([bytes[6], bytes[7]]),
+            data4: [bytes[8], bytes[9], bytes[10], bytes[11], bytes[12], bytes[13], bytes[14], bytes[15]],
+        }
+    }
+
+    /// Returns the 16-byte mixed-endian form of the GUID, see [`Guid::from_gpt_bytes`].
+    pub const fn to_gpt_bytes(&self) -> [u8; 16] {
+        let a = self.data1.to_le_bytes();
+        let b = self.data2.to_le_bytes();
+        let c = self.data3.to_le_bytes();
+        let d = self.data4;
+        [a[0], a[1], a[2], a[3], b[0], b[1], c[0], c[1], d[0], d[1], d[2], d[3], d[4], d[5], d[6], d[7]]
+    }
+
+    /// Parses a GUID in the form `xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx`, optionally
+    /// surrounded by braces. Hexadecimal digits are case-insensitive.
+    ///
+    /// # Errors
+    /// Returns a [`ParseError`] if the input is not a well-formed GUID.
+    pub fn parse(s: &str) -> Result<Self, ParseError> {
+        let inner = match s.strip_prefix('{') {
+            Some(rest) => rest.strip_suffix('}'),
+            None => Some(s),
+        };
+        inner
+            .and_then(parse_hyphenated)
+            .ok_or_else(|| ParseError::new("GUID", s, "expected xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx"))
+    }
+}
+
+impl FromStr for Guid {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Guid::parse(s)
+    }
+}
+
+impl TryFrom<String> for Guid {
+    type Error = ParseError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Guid::parse(&s)
+    }
+}
+
+impl From<Guid> for String {
+    fn from(guid: Guid) -> Self {
+        guid.to_string()
+    }
+}
+
+impl fmt::Display for Guid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let d = &self.data4;
+        write!(
+            f,
+            "{:08x}-{:04x}-{:04x}-{:02x}{:02x}-{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}",
+            self.data1, self.data2, self.data3, d[0], d[1], d[2], d[3], d[4], d[5], d[6], d[7]
+        )
+    }
+}
+
+/// 解析不带花括号的 GUID，格式错误时返回 `None`
+fn parse_hyphenated(s: &str) -> Option<Guid> {
+    let bytes = s.as_bytes();
+    if bytes.len() != 36 {
+        return None;
+    }
+
+    // 先校验连字符位置，再逐字节解析十六进制，避免 `from_str_radix` 接受 `+` 号
+    let mut digits = [0u8; 32];
+    let mut count = 0;
+    for (i, &b) in bytes.iter().enumerate() {
+        match i {
+            8 | 13 | 18 | 23 if b == b'-' => {}
+            8 | 13 | 18 | 23 => return None,
+            _ => {
+                digits[count] = (b as char).to_digit(16)? as u8;
+                count += 1;
+            }
+        }
+    }
+
+    let value = digits.iter().fold(0u128, |acc, &digit| acc << 4 | digit as u128);
+    let data4 = (value as u64).to_be_bytes();
+    Some(Guid {
+        data1: (value >> 96) as u32,
+        data2: (value >> 80) as u16,
+        data3: (value >> 64) as u16,
+        data4,
+    })
+}
+
+/// A volume GUID path such as `\\?\Volume{e8a7f3c2-1234-5678-9abc-def012345678}\`.
+///
+/// Parsing is case-insensitive and accepts the path with or without its trailing
+/// backslash. Formatting always produces the canonical form, with a lowercase GUID and a
+/// trailing backslash, which is what `FindFirstVolumeW` and
+/// `GetVolumeNameForVolumeMountPointW` return.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(into = "String", try_from = "String"))]
+pub struct VolumeGuidPath(Guid);
+
+impl VolumeGuidPath {
+    /// Creates the volume GUID path of the volume identified by `guid`.
+    pub const fn new(guid: Guid) -> Self {
+        VolumeGuidPath(guid)
+    }
+
+    /// Parses a volume GUID path.
+    ///
+    /// # Errors
+    /// Returns a [`ParseError`] if the input does not have the form
+    /// `\\?\Volume{xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx}`, optionally followed by a
+    /// backslash.
+    pub fn parse(s: &str) -> Result<Self, ParseError> {
+        let error = || ParseError::new("volume GUID path", s, "malformed volume GUID path");
+
+        let rest = strip_prefix_ignore_case(s, PREFIX).ok_or_else(error)?;
+        let rest = rest.strip_suffix('\\').unwrap_or(rest);
+        let guid = rest.strip_suffix('}').and_then(parse_hyphenated).ok_or_else(error)?;
+        Ok(VolumeGuidPath(guid))
+    }
+
+    /// Returns `true` if `s` starts like a volume GUID path, whether or not the rest of
+    /// it is well-formed.
+    pub fn has_prefix(s: &str) -> bool {
+        strip_prefix_ignore_case(s, PREFIX).is_some()
+    }
+
+    /// Returns the GUID of the volume.
+    pub const fn guid(&self) -> Guid {
+        self.0
+    }
+}
+
+impl FromStr for VolumeGuidPath {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        VolumeGuidPath::parse(s)
+    }
+}
+
+impl TryFrom<String> for VolumeGuidPath {
+    type Error = ParseError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        VolumeGuidPath::parse(&s)
+    }
+}
+
+impl From<VolumeGuidPath> for String {
+    fn from(path: VolumeGuidPath) -> Self {
+        path.to_string()
+    }
+}
+
+impl fmt::Display for VolumeGuidPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, r"{}{}}}\", PREFIX, self.0)
+    }
+}
+
+impl From<Guid> for VolumeGuidPath {
+    fn from(guid: Guid) -> Self {
+        VolumeGuidPath(guid)
+    }
+}
+
+/// 不区分 ASCII 大小写地去除前缀
+fn strip_prefix_ignore_case<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = s.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix).then(|| &s[prefix.len()..])
+}
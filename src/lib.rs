@@ -15,310 +15,183 @@
  * limitations under the License.
  */
 
-use std::collections::HashMap;
 use std::io;
-use std::sync::{Arc, Mutex};
-
-// 使用lazy_static初始化全局卷映射表
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+
+mod backend;
+mod error;
+mod fake;
+pub mod fs;
+pub mod guid;
+pub mod matching;
+pub mod mountinfo;
+pub mod mountvol;
+mod offline;
+#[cfg(feature = "powershell")]
+pub mod powershell;
+mod resolver;
+#[cfg(feature = "serde")]
+mod schema;
+mod volume;
+#[cfg(any(windows, test))]
+mod wide;
+pub mod winpath;
+
+pub use backend::{SystemBackend, VolumeBackend};
+pub use error::Error;
+pub use fake::FakeBackend;
+pub use offline::OfflineBackend;
+pub use resolver::{Batch, Granularity, InitStatus, RefreshPolicy, Resolver, ResolverBuilder, Volumes};
+pub use volume::{DevicePath, MountPoint, ParseError, Resolution, VolumeId, VolumeInfo, VolumeMetadata};
+
+#[cfg(feature = "serde")]
+pub use schema::export_volume_map;
+
+#[cfg(windows)]
+mod windows;
+#[cfg(windows)]
+pub use windows::WindowsBackend;
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "linux")]
+pub use linux::LinuxBackend;
+
+/// A specialized [`Result`](std::result::Result) type for the fallible functions of this
+/// crate.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+// 使用lazy_static初始化全局默认解析器
 lazy_static::lazy_static! {
-    /// 全局卷映射表，存储挂载点路径到卷设备路径的映射
-    // 使用Arc<Mutex<>>实现线程安全访问
-    static ref VOLUME_MAP: Arc<Mutex<HashMap<String, String>>> = {
-        Arc::new(Mutex::new(
-            // 初始化时构建卷映射表，失败时打印错误并返回空表
-            build_volume_map().unwrap_or_else(|e| {
-                eprintln!("Failed to initialize volume map: {}", e);
-                HashMap::new()
-            })
-        ))
-    };
+    /// 全局默认解析器，模块级函数均委托给它
+    // 卷映射表在首次使用时构建
+    // 通过原子指针替换，读取方无需加锁
+    static ref DEFAULT_RESOLVER: ArcSwap<Resolver> = ArcSwap::from_pointee(Resolver::new());
 }
 
-/// Windows API FFI绑定模块
-mod winapi {
-    #[link(name = "kernel32")]
-    unsafe extern "system" {
-        // 卷管理相关 API
-
-        /// 查找第一个卷设备，返回搜索句柄
-        ///
-        /// # 参数
-        /// - `lpsz_volume_name`: 接收卷名的缓冲区。缓冲区应至少为 MAX_PATH+1 宽字符
-        /// - `cch_buffer_length`: 缓冲区大小（以宽字符计），包含终止空字符
-        ///
-        /// # 返回值
-        /// - 成功时返回搜索句柄
-        /// - 失败时返回 INVALID_HANDLE_VALUE
-        ///
-        /// # 安全性
-        /// 需要确保缓冲区足够大并有效
-        ///
-        /// [微软文档](https://docs.microsoft.com/en-us/windows/win32/api/fileapi/nf-fileapi-findfirstvolumew)
-        pub fn FindFirstVolumeW(
-            lpsz_volume_name: *mut u16,
-            cch_buffer_length: u32,
-        ) -> *mut std::ffi::c_void;
-
-        /// 查找下一个卷设备
-        ///
-        /// # 参数
-        /// - `h_find_volume`: 由 FindFirstVolumeW 返回的搜索句柄
-        /// - `lpsz_volume_name`: 接收卷名的缓冲区
-        /// - `cch_buffer_length`: 缓冲区大小（以宽字符计）
-        ///
-        /// # 返回值
-        /// - 成功返回非零值
-        /// - 失败返回 0（应调用 GetLastError 获取错误信息）
-        ///
-        /// # 安全性
-        /// 需要确保句柄有效且缓冲区足够大
-        ///
-        /// [微软文档](https://docs.microsoft.com/en-us/windows/win32/api/fileapi/nf-fileapi-findnextvolumew)
-        pub fn FindNextVolumeW(
-            h_find_volume: *mut std::ffi::c_void,
-            lpsz_volume_name: *mut u16,
-            cch_buffer_length: u32,
-        ) -> i32;
-
-        /// 关闭卷搜索句柄
-        ///
-        /// # 参数
-        /// - `h_find_volume`: 要关闭的搜索句柄
-        ///
-        /// # 返回值
-        /// - 成功返回非零值
-        /// - 失败返回 0
-        ///
-        /// # 安全性
-        /// 需要确保句柄有效且未被重复关闭
-        ///
-        /// [微软文档](https://docs.microsoft.com/en-us/windows/win32/api/fileapi/nf-fileapi-findvolumeclose)
-        pub fn FindVolumeClose(h_find_volume: *mut std::ffi::c_void) -> i32;
-
-        /// 获取指定卷的所有挂载点路径
-        ///
-        /// # 参数
-        /// - `lpsz_volume_name`: 输入卷名（GUID 格式），需以反斜杠结尾
-        /// - `lpsz_volume_path_names`: 接收路径列表的缓冲区（多个以空字符分隔的路径）
-        /// - `cch_buffer_length`: 缓冲区大小（以宽字符计）
-        /// - `pcch_return_length`: 接收实际需要的缓冲区大小（不含终止符）
-        ///
-        /// # 返回值
-        /// - 成功返回非零值
-        /// - 失败返回 0（若缓冲区不足，会返回 ERROR_MORE_DATA）
-        ///
-        /// # 安全性
-        /// 需要确保输入卷名格式正确，缓冲区足够大
-        ///
-        /// [微软文档](https://docs.microsoft.com/en-us/windows/win32/api/fileapi/nf-fileapi-getvolumepathnamesforvolumenamew)
-        pub fn GetVolumePathNamesForVolumeNameW(
-            lpsz_volume_name: *const u16,
-            lpsz_volume_path_names: *mut u16,
-            cch_buffer_length: u32,
-            pcch_return_length: *mut u32,
-        ) -> i32;
-
-        // 路径处理相关 API
-
-        /// 获取文件完整路径（展开相对路径和环境变量）
-        ///
-        /// # 参数
-        /// - `lp_file_name`: 输入路径（宽字符字符串）
-        /// - `n_buffer_length`: 输出缓冲区大小（宽字符数）
-        /// - `lp_buffer`: 接收完整路径的缓冲区
-        /// - `lp_file_part`: 接收文件名部分起始位置的指针（可为 null）
-        ///
-        /// # 返回值
-        /// - 成功返回复制到缓冲区的字符数（不含终止符）
-        /// - 若缓冲区不足，返回所需缓冲区大小（含终止符）
-        /// - 失败返回 0
-        ///
-        /// # 安全性
-        /// 需要确保输入指针有效，缓冲区足够大
-        ///
-        /// [微软文档](https://docs.microsoft.com/en-us/windows/win32/api/fileapi/nf-fileapi-getfullpathnamew)
-        pub fn GetFullPathNameW(
-            lp_file_name: *const u16,
-            n_buffer_length: u32,
-            lp_buffer: *mut u16,
-            lp_file_part: *mut *mut u16,
-        ) -> u32;
-
-        /// 获取路径所属的卷挂载点
-        ///
-        /// # 参数
-        /// - `lpsz_file_name`: 输入文件路径（宽字符字符串）
-        /// - `lpsz_volume_path_name`: 输出挂载点路径的缓冲区
-        /// - `cch_buffer_length`: 缓冲区大小（宽字符数）
-        ///
-        /// # 返回值
-        /// - 成功返回非零值
-        /// - 失败返回 0
-        ///
-        /// # 安全性
-        /// 需要确保缓冲区足够大（通常至少 MAX_PATH 长度）
-        ///
-        /// [微软文档](https://docs.microsoft.com/en-us/windows/win32/api/fileapi/nf-fileapi-getvolumepathnamew)
-        pub fn GetVolumePathNameW(
-            lpsz_file_name: *const u16,
-            lpsz_volume_path_name: *mut u16,
-            cch_buffer_length: u32,
-        ) -> i32;
-    }
+/// Returns the default [`Resolver`] the free functions of this crate delegate to.
+///
+/// Unless replaced with [`set_default_resolver`] or [`set_backend`], it uses the
+/// [`SystemBackend`] and [`RefreshPolicy::Manual`].
+pub fn default_resolver() -> Arc<Resolver> {
+    DEFAULT_RESOLVER.load_full()
 }
 
-/// Windows API调用结果类型别名
-type WinResult<T> = Result<T, io::Error>;
-
-/// 将Rust字符串转换为Windows宽字符字符串
-fn wide_string(s: &str) -> Vec<u16> {
-    use std::ffi::OsStr;
-    use std::os::windows::ffi::OsStrExt as _;
+/// Replaces the default [`Resolver`] the free functions of this crate delegate to.
+///
+/// Existing handles obtained from [`default_resolver`] keep using the previous instance.
+pub fn set_default_resolver(resolver: Resolver) {
+    DEFAULT_RESOLVER.store(Arc::new(resolver));
+}
 
-    OsStr::new(s)
-        .encode_wide()  // 转换为 UTF-16 编码迭代器
-        .chain(Some(0)) // 追加终止符
-        .collect()      // collect as Vec<u16>
+/// Builds the volume mapping table of the default resolver if it has not been built
+/// successfully yet, and returns the number of volume mappings.
+///
+/// The table is otherwise built on first use, and a failure there only shows up as
+/// `None`/`false` results. Calling this at startup surfaces the root cause instead.
+/// If it fails, the build is retried on the next call of any function of this crate.
+///
+/// # Errors
+/// - [`Error::VolumeEnumeration`]: Enumerating the volumes of the system failed
+///
+/// # Example
+/// ```rust
+/// match samevol::try_init() {
+///     Ok(count) => println!("Loaded {} volume mappings", count),
+///     Err(e) => eprintln!("Volume detection unavailable: {}", e),
+/// }
+/// ```
+pub fn try_init() -> Result<usize> {
+    DEFAULT_RESOLVER.load().try_init()
 }
 
-/// 从宽字符缓冲区读取终止字符串
-fn from_wide_buf(buffer: &[u16]) -> WinResult<String> {
-    // 找到第一个终止符的位置
-    let end = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
-    // 转换为UTF-8字符串
-    String::from_utf16(&buffer[..end])
-        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "UTF-16 conversion failed"))
+/// Builds the volume mapping table of the default resolver if it has not been built
+/// successfully yet, and returns the number of volume mappings.
+///
+/// Unlike [`try_init`], this never fails and never panics: a failure yields `0`, and the
+/// error is kept for [`init_status`]. The build is retried on the next call of any
+/// function of this crate.
+///
+/// # Example
+/// ```rust
+/// if samevol::init() == 0 {
+///     // Fall back to copying, and report `samevol::init_status()` in the diagnostics
+/// }
+/// ```
+pub fn init() -> usize {
+    DEFAULT_RESOLVER.load().init()
 }
 
-/// 构建系统卷到挂载点路径的映射表
-fn build_volume_map() -> WinResult<HashMap<String, String>> {
-    let mut volume_map = HashMap::new();
-
-    /* 卷名缓冲区说明：
-     * 格式：`\\?\Volume{xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx}\`
-     * 总长度：4(前缀`\\?\`) + 7(`Volume{`) + 36(GUID) + 2(`}\`) + 1(`\0`) = 50 个宽字符
-     */
-    let mut buffer = [0u16; 50];
-
-    // 启动卷枚举
-    let handle = unsafe { winapi::FindFirstVolumeW(buffer.as_mut_ptr(), buffer.len() as u32) };
-    if handle.is_null() {
-        return Err(io::Error::last_os_error());
-    }
-
-    // 遍历所有卷设备
-    loop {
-        // 转换当前卷名
-        let volume_name = from_wide_buf(&buffer)?;
-
-        // 准备路径缓冲区（4KiB）
-        let mut paths_buffer = [0u16; 4096];
-        let mut returned_len = 0;
-        // 获取该卷的所有挂载点路径
-        let success = unsafe {
-            winapi::GetVolumePathNamesForVolumeNameW(
-                buffer.as_ptr(),           // 输入卷名
-                paths_buffer.as_mut_ptr(), // 输出路径列表
-                paths_buffer.len() as u32, // 缓冲区大小
-                &mut returned_len,         // 接收实际需要大小
-            )
-        };
-
-        // 处理获取到的路径
-        if success != 0 && returned_len > 0 {
-            let mut offset = 0;
-            // 遍历多重null终止的路径列表
-            while offset < paths_buffer.len() {
-                if paths_buffer[offset] == 0 {
-                    break; // 遇到双重终止符，结束遍历
-                }
-
-                // 提取单个路径
-                let end = paths_buffer[offset..]
-                    .iter()
-                    .position(|&c| c == 0)
-                    .unwrap_or(paths_buffer.len() - offset);
-                let path = from_wide_buf(&paths_buffer[offset..offset + end])?;
-
-                // 规范化路径格式：统一使用反斜杠并确保结尾反斜杠
-                let normalized_path = path.replace('/', "\\");
-                let key = if !normalized_path.ends_with('\\') {
-                    format!("{}\\", normalized_path) // 追加反斜杠用于前缀匹配
-                } else {
-                    normalized_path
-                };
-
-                // 插入映射表（挂载点路径 -> 卷设备路径）
-                volume_map.insert(key, volume_name.clone());
-
-                offset += end + 1; // 移动到下一个路径
-            }
-        }
-
-        // 获取下一个卷
-        let next = unsafe {
-            buffer.fill(0); // 清空缓冲区
-            winapi::FindNextVolumeW(handle, buffer.as_mut_ptr(), buffer.len() as u32)
-        };
-        if next == 0 {
-            // 枚举完成或出错
-            break;
-        }
-    }
-
-    // 关闭卷搜索句柄
-    unsafe { winapi::FindVolumeClose(handle) };
-    Ok(volume_map)
+/// Returns the initialization status of the volume mapping table of the default resolver.
+///
+/// # Example
+/// ```rust
+/// use samevol::{init_status, InitStatus};
+///
+/// if let InitStatus::Failed(e) = init_status() {
+///     eprintln!("Volume map unavailable: {}", e);
+/// }
+/// ```
+pub fn init_status() -> InitStatus {
+    DEFAULT_RESOLVER.load().init_status()
 }
 
-/// 获取给定路径所在的卷挂载点
-fn get_volume_mount_point(path: &str) -> WinResult<String> {
-    // 转换为宽字符路径
-    let path_wide = wide_string(path);
-    let mut full_path = [0u16; 4096];
-    let mut mount_point = [0u16; 4096];
-
-    // 第一步：获取绝对路径
-    let len = unsafe {
-        winapi::GetFullPathNameW(
-            path_wide.as_ptr(),     // 输入路径
-            full_path.len() as u32, // 输出缓冲区大小
-            full_path.as_mut_ptr(), // 输出缓冲区
-            std::ptr::null_mut(),   // 不需要文件名部分
-        )
-    };
-    if len == 0 {
-        return Err(io::Error::last_os_error());
-    }
-
-    // 第二步：获取挂载点路径
-    let success = unsafe {
-        winapi::GetVolumePathNameW(
-            full_path.as_ptr(),       // 输入绝对路径
-            mount_point.as_mut_ptr(), // 输出挂载点路径
-            mount_point.len() as u32, // 缓冲区大小
-        )
-    };
-    if success == 0 {
-        return Err(io::Error::last_os_error());
-    }
-
-    // 转换结果并确保以反斜杠结尾
-    from_wide_buf(&mount_point).map(|s| {
-        if s.ends_with('\\') { s } else { format!("{}\\", s) }
-    })
+/// Replaces the volume backend used by the functions of this crate and rebuilds the
+/// volume mapping table from it.
+///
+/// This is mainly useful in tests, to run code that calls [`is_same_vol`] or
+/// [`resolve_device_path`] against a mount layout declared with a [`FakeBackend`].
+///
+/// The new backend is installed as a fresh default [`Resolver`] with
+/// [`RefreshPolicy::Manual`], replacing the previous one only if its table could be built.
+///
+/// # Returns
+/// - `Ok(usize)`: Number of volume mappings found by the new backend
+/// - `Err(io::Error)`: Error encountered while building the table. The previous default
+///   resolver stays in place.
+///
+/// # Example
+/// ```rust
+/// use samevol::{FakeBackend, is_same_vol, set_backend, SystemBackend};
+///
+/// let backend = FakeBackend::windows()
+///     .with_volume(r"\\?\Volume{11111111-1111-1111-1111-111111111111}\", [r"D:\"])
+///     .with_volume(r"\\?\Volume{22222222-2222-2222-2222-222222222222}\", [r"D:\Vdisks\Wechat"]);
+/// set_backend(backend).unwrap();
+///
+/// assert!(is_same_vol(r"D:\", r"D:\Vdisks\file.txt"));
+/// assert!(!is_same_vol(r"D:\", r"D:\Vdisks\Wechat\file.txt"));
+///
+/// // Switch back to the real system
+/// set_backend(SystemBackend::default()).unwrap();
+/// ```
+pub fn set_backend<B: VolumeBackend + 'static>(backend: B) -> Result<usize, io::Error> {
+    let resolver = Resolver::builder().backend(backend).build();
+    let count = resolver.reinitialize()?;
+    set_default_resolver(resolver);
+    Ok(count)
 }
 
 // 重新初始化卷映射表
 // 返回操作结果（成功包含映射数量，失败包含错误信息）
 /// Re-initializes the volume mapping table by rebuilding it from the system.
 ///
+/// On Windows the table is enumerated with `FindFirstVolumeW`, on Linux it is parsed
+/// from `/proc/self/mountinfo`. If another backend was installed with [`set_backend`],
+/// the table is rebuilt from that backend instead.
+///
 /// # Returns
 /// - `Ok(usize)`: Number of volume mappings found
 /// - `Err(io::Error)`: Error encountered during rebuilding
 ///
 /// # Notes
-/// This will lock the global volume map mutex during update.
+/// This rebuilds the table of the [default resolver](default_resolver). Concurrent
+/// lookups are not blocked, they keep using the previous table until the new one is
+/// swapped in.
 ///
 /// # Example
 ///
@@ -330,31 +203,126 @@ fn get_volume_mount_point(path: &str) -> WinResult<String> {
 /// println!("Reloaded {} volume mappings", count);
 /// ```
 pub fn reinitialize_volume_map() -> Result<usize, io::Error> {
-    let new_map = build_volume_map()?;
-    let count = new_map.len();
+    Ok(DEFAULT_RESOLVER.load().reinitialize()?)
+}
 
-    // 锁定并更新全局映射表
-    let mut map = VOLUME_MAP.lock()
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("Mutex poison error: {}", e)))?;
+/// Returns a snapshot of all volumes of the system and their mount points.
+///
+/// On Windows a volume may be reported with several mount points (a drive letter and
+/// folder mount points, as returned by `GetVolumePathNamesForVolumeNameW`), or with none
+/// at all. On Linux every mounted filesystem is reported with the mount points listed in
+/// `/proc/self/mountinfo`.
+///
+/// # Errors
+/// - [`Error::VolumeEnumeration`]: The volume map has not been built yet and building it
+///   failed (it will be retried on the next call)
+///
+/// # Example
+/// ```rust
+/// use samevol::volumes;
+///
+/// for volume in &volumes().expect("Failed to enumerate volumes") {
+///     println!("{}", volume.id());
+///     for mount_point in volume.mount_points() {
+///         println!("    {}", mount_point);
+///     }
+/// }
+/// ```
+pub fn volumes() -> Result<Volumes> {
+    DEFAULT_RESOLVER.load().volumes()
+}
 
-    *map = new_map;
-    Ok(count)
+/// Returns the mount points of a volume, given its device path.
+///
+/// This is the reverse of [`resolve_device_path`]. The device path is brought into its
+/// canonical form first, so volume GUID paths match regardless of case and trailing
+/// backslash.
+///
+/// # Returns
+/// - `Some(Vec<MountPoint>)`: The mount points of the volume, possibly empty if the
+///   volume is not mounted anywhere
+/// - `None`: If the volume is unknown or the volume map could not be built
+///
+/// # Example
+/// ```rust
+/// use samevol::{mount_points, resolve_device_path};
+///
+/// let device_path = resolve_device_path(r"C:\Windows").expect("Failed to resolve volume");
+/// let mount_points = mount_points(&device_path).unwrap();
+/// println!("{} is mounted at {:?}", device_path, mount_points);
+/// ```
+pub fn mount_points(device_path: &str) -> Option<Vec<MountPoint>> {
+    DEFAULT_RESOLVER.load().mount_points(device_path)
+}
+
+/// Queries the filesystem attributes and capacity of the volume containing a path.
+///
+/// On Windows the attributes are queried with `GetVolumeInformationW` and
+/// `GetDiskFreeSpaceExW`. On Linux the filesystem type is taken from
+/// `/proc/self/mountinfo` and the capacity from `statvfs`; the label and serial number are
+/// not reported. The values are queried on every call and are not cached.
+///
+/// # Errors
+/// - The errors of [`try_resolve_device_path`], if the path cannot be resolved
+/// - [`Error::Metadata`]: The volume was resolved but querying its metadata failed, e.g.
+///   because a removable drive holds no media
+///
+/// # Example
+/// ```rust
+/// use samevol::volume_metadata;
+///
+/// match volume_metadata(r"C:\Windows") {
+///     Ok(metadata) => println!(
+///         "{} ({:?}): {:?} of {:?} bytes free",
+///         metadata.label().unwrap_or("<no label>"),
+///         metadata.fs_type(),
+///         metadata.available_bytes(),
+///         metadata.total_bytes(),
+///     ),
+///     Err(e) => eprintln!("Failed to query volume: {}", e),
+/// }
+/// ```
+pub fn volume_metadata<P: AsRef<Path>>(path: P) -> Result<VolumeMetadata> {
+    DEFAULT_RESOLVER.load().volume_metadata(path)
+}
+
+/// Queries the filesystem attributes and capacity of a volume listed by [`volumes`].
+///
+/// See [`volume_metadata`] for the attributes reported on each platform.
+///
+/// # Errors
+/// - [`Error::Metadata`]: Querying the metadata failed
+///
+/// # Example
+/// ```rust
+/// use samevol::{query_metadata, volumes};
+///
+/// for volume in &volumes().expect("Failed to enumerate volumes") {
+///     if let Ok(metadata) = query_metadata(volume) {
+///         println!("{}: {:?}", volume.id(), metadata.fs_type());
+///     }
+/// }
+/// ```
+pub fn query_metadata(volume: &VolumeInfo) -> Result<VolumeMetadata> {
+    DEFAULT_RESOLVER.load().query_metadata(volume)
 }
 
 /// Resolves the device path of volume for a given file system path.
 ///
 /// # Arguments
-/// * `path` - The file system path to resolve (can be absolute or relative)
+/// * `path` - The file system path to resolve (can be absolute or relative). Any
+///   [`AsRef<Path>`] is accepted, including paths that are not valid Unicode
 ///
 /// # Returns
-/// - `Some(String)`: The device path in the format
-///   `\\?\Volume{xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx}\`
+/// - `Some(String)`: The device path, in the format
+///   `\\?\Volume{xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx}\` on Windows, or the
+///   `major:minor` device number of the mounted filesystem (e.g. `8:1`) on Linux
 /// - `None`: If the path cannot be resolved or the volume mapping is not found
 ///
 /// # Errors
 /// This function may return `None` in the following cases:
 /// - The input path is invalid or inaccessible
-/// - The volume map has not been properly initialized
+/// - The volume map could not be built (see [`try_init`] to get the error)
 /// - The path does not match any known mount points
 ///
 /// # Example
@@ -367,30 +335,134 @@ pub fn reinitialize_volume_map() -> Result<usize, io::Error> {
 /// ```
 ///
 /// # Notes
-/// - The function uses the global volume map initialized at startup
+/// - The function uses the volume map of the [default resolver](default_resolver),
+///   built on first use and rebuilt on every call until that succeeds
 /// - For relative paths, the current working directory is used as the base
-/// - The returned device path includes the `\\?\` prefix and trailing backslash
-pub fn resolve_device_path(path: &str) -> Option<String> {
-    // 获取挂载点路径
-    let mount_point = match get_volume_mount_point(path) {
-        Ok(m) => m,
-        Err(_) => return None,
-    };
-
-    // 获取锁并访问映射表
-    let map = VOLUME_MAP.lock().ok()?;
-
-    // 查找所有可能的前缀匹配项
-    let candidates = map.keys()
-        .filter(|k| mount_point.starts_with(*k))
-        .collect::<Vec<_>>();
-
-    // 选择最长匹配的挂载点路径（最精确的父路径）
-    let mount_path = candidates.iter()
-        .max_by_key(|k| k.len())?;
-
-    // 获取对应的设备路径
-    map.get(*mount_path).cloned()
+/// - On Windows, the returned device path includes the `\\?\` prefix and trailing backslash
+/// - On Linux, symbolic links in the existing part of the path are resolved before
+///   matching it against the mount points in `/proc/self/mountinfo`
+pub fn resolve_device_path<P: AsRef<Path>>(path: P) -> Option<String> {
+    DEFAULT_RESOLVER.load().resolve_device_path(path)
+}
+
+/// Resolves the device path of volume for a given file system path, reporting why it
+/// could not be resolved.
+///
+/// This is the fallible counterpart of [`resolve_device_path`].
+///
+/// # Errors
+/// - [`Error::NotFound`], [`Error::PermissionDenied`] or [`Error::InvalidPath`]: The path
+///   could not be expanded or mapped to a mount point, with the OS error and the path
+/// - [`Error::VolumeEnumeration`]: The volume map has not been built yet and building it
+///   failed (it will be retried on the next call)
+/// - [`Error::NoMountPoint`]: The mount point of the path does not match any volume
+///
+/// # Example
+/// ```rust
+/// use samevol::{try_resolve_device_path, Error};
+///
+/// match try_resolve_device_path(r"C:\Windows\System32") {
+///     Ok(device_path) => println!("Device path: {}", device_path),
+///     Err(Error::PermissionDenied { path, .. }) => eprintln!("Access to {} denied", path.display()),
+///     Err(e) => eprintln!("Failed to resolve volume: {}", e),
+/// }
+/// ```
+pub fn try_resolve_device_path<P: AsRef<Path>>(path: P) -> Result<String> {
+    DEFAULT_RESOLVER.load().try_resolve_device_path(path)
+}
+
+/// Resolves the identity of the volume containing a given file system path.
+///
+/// This is the typed counterpart of [`resolve_device_path`]: the returned [`VolumeId`] is
+/// cheap to clone and can be compared, hashed and ordered, e.g. to group paths by volume.
+///
+/// # Example
+/// ```rust
+/// use samevol::resolve_volume;
+///
+/// let id = resolve_volume(r"C:\Windows\System32").expect("Failed to resolve volume");
+/// assert_eq!(Some(id), resolve_volume(r"C:\Windows"));
+/// ```
+pub fn resolve_volume<P: AsRef<Path>>(path: P) -> Option<VolumeId> {
+    DEFAULT_RESOLVER.load().resolve_volume(path)
+}
+
+/// Resolves the identity of the volume containing a given file system path, reporting why
+/// it could not be resolved.
+///
+/// See [`try_resolve_device_path`] for the possible errors.
+pub fn try_resolve_volume<P: AsRef<Path>>(path: P) -> Result<VolumeId> {
+    DEFAULT_RESOLVER.load().try_resolve_volume(path)
+}
+
+/// Resolves the volumes of many paths at once.
+///
+/// Compared to calling [`try_resolve_volume`] for every path, all paths are resolved
+/// against the same snapshot of the volume mapping table, and the mount point query is
+/// made once per parent directory instead of once per path.
+///
+/// # Arguments
+/// * `paths` - The paths to resolve, of any type implementing [`AsRef<Path>`]
+///
+/// # Returns
+/// One result per path, in the order of `paths`. See [`try_resolve_device_path`] for the
+/// possible errors.
+///
+/// # Example
+/// ```rust
+/// use samevol::resolve_many;
+///
+/// let results = resolve_many([r"C:\Windows", r"C:\Windows\System32"]);
+/// assert_eq!(results.len(), 2);
+/// for result in results {
+///     println!("{:?}", result);
+/// }
+/// ```
+pub fn resolve_many<I, P>(paths: I) -> Vec<Result<VolumeId>>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    DEFAULT_RESOLVER.load().resolve_many(paths)
+}
+
+/// Groups many paths by the volume they reside on.
+///
+/// Works like [`resolve_many`], then collects the paths (as given) per volume, keeping
+/// their relative order. Paths that cannot be resolved are left out; use
+/// [`resolve_many`] to find out why.
+///
+/// # Example
+/// ```rust
+/// use samevol::group_by_volume;
+///
+/// // e.g. to schedule one copy job per disk
+/// for (volume, paths) in group_by_volume([r"C:\a.txt", r"D:\b.txt", r"C:\c.txt"]) {
+///     println!("{}: {} file(s)", volume, paths.len());
+/// }
+/// ```
+pub fn group_by_volume<I, P>(paths: I) -> HashMap<VolumeId, Vec<PathBuf>>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    DEFAULT_RESOLVER.load().group_by_volume(paths)
+}
+
+/// Resolves the volumes of many paths at once, in parallel on the rayon thread pool.
+///
+/// See [`resolve_many`] for details. Requires the `rayon` feature.
+#[cfg(feature = "rayon")]
+pub fn par_resolve_many<P: AsRef<Path> + Sync>(paths: &[P]) -> Vec<Result<VolumeId>> {
+    DEFAULT_RESOLVER.load().par_resolve_many(paths)
+}
+
+/// Groups many paths by the volume they reside on, in parallel on the rayon thread pool.
+///
+/// See [`group_by_volume`] for details. Requires the `rayon` feature.
+#[cfg(feature = "rayon")]
+pub fn par_group_by_volume<P: AsRef<Path> + Sync>(paths: &[P]) -> HashMap<VolumeId, Vec<PathBuf>> {
+    DEFAULT_RESOLVER.load().par_group_by_volume(paths)
 }
 
 /// Checks if two paths reside on the same volume.
@@ -399,14 +471,21 @@ pub fn resolve_device_path(path: &str) -> Option<String> {
 /// * `path1` - First path to check
 /// * `path2` - Second path to check
 ///
+/// Both accept any [`AsRef<Path>`], such as `&str`, [`Path`] or [`OsString`](std::ffi::OsString).
+///
 /// # Returns
-/// `true` if both paths are on the same volume, `false` otherwise (including error cases).
+/// `true` if both paths are on the same volume, `false` otherwise (including error cases,
+/// use [`try_is_same_vol`] to tell them apart).
 ///
 /// # Implementation Details
 /// 1. Resolves each path's mount point
 /// 2. Finds the longest matching mount point path in the volume map
 /// 3. Compares the underlying device paths
 ///
+/// Being on the same volume does not guarantee that a file can be renamed from one path
+/// to the other: on Linux, two bind mounts of the same filesystem are the same volume, but
+/// `rename(2)` between them fails with `EXDEV`. Use [`can_rename`] to ask that question.
+///
 /// # Example
 /// ```rust
 /// use samevol::is_same_vol;
@@ -416,10 +495,115 @@ pub fn resolve_device_path(path: &str) -> Option<String> {
 ///
 /// println!("Same volume? {}", is_same_vol(path1, path2)); // false
 /// ```
-pub fn is_same_vol(path1: &str, path2: &str) -> bool {
-    // 比较两个路径所在卷的设备路径 (device path)
-    let vol1 = resolve_device_path(path1);
-    let vol2 = resolve_device_path(path2);
+pub fn is_same_vol<P: AsRef<Path>, Q: AsRef<Path>>(path1: P, path2: Q) -> bool {
+    DEFAULT_RESOLVER.load().is_same_vol(path1, path2)
+}
 
-    vol1.zip(vol2).is_some_and(|(v1, v2)| v1 == v2)
+/// Predicts whether `src` can be renamed to `dst` without copying, i.e. whether
+/// [`std::fs::rename`] will not fail with a cross-device error.
+///
+/// This differs from [`is_same_vol`], which compares volumes (filesystems), in that it
+/// compares mounts:
+/// - On Linux, `rename(2)` works only within a single mount. Two paths on the same
+///   filesystem but reached through different mounts (e.g. a bind mount of `/home` at
+///   `/mnt/home`) are on the same volume, yet renaming between them fails with `EXDEV`.
+///   Each entry of `/proc/self/mountinfo` (each mount ID) is treated as its own mount.
+///   The mount IDs of both paths are queried live (`statx` with `STATX_MNT_ID`), so
+///   mounts created after the volume mapping table was built are taken into account.
+/// - On Windows, renaming works anywhere within a volume, even across two folder mount
+///   points of it, so this is equivalent to [`is_same_vol`].
+///
+/// `dst` does not need to exist; it is resolved through its existing ancestors. Other
+/// reasons why a rename may fail (permissions, `dst` being a non-empty directory, `src`
+/// being a mount point itself, ...) are not checked.
+///
+/// # Returns
+/// `true` if a rename is expected to work, `false` otherwise (including error cases, use
+/// [`try_can_rename`] to tell them apart).
+///
+/// # Example
+/// ```rust
+/// use samevol::can_rename;
+///
+/// let (src, dst) = ("/home/user/file.tmp", "/mnt/home/user/file");
+/// if can_rename(src, dst) {
+///     // std::fs::rename(src, dst)
+/// } else {
+///     // copy, then delete the source
+/// }
+/// ```
+pub fn can_rename<P: AsRef<Path>, Q: AsRef<Path>>(src: P, dst: Q) -> bool {
+    DEFAULT_RESOLVER.load().can_rename(src, dst)
+}
+
+/// Predicts whether `src` can be renamed to `dst` without copying, reporting why it could
+/// not be determined.
+///
+/// This is the fallible counterpart of [`can_rename`]. See [`try_resolve_device_path`]
+/// for the possible errors.
+pub fn try_can_rename<P: AsRef<Path>, Q: AsRef<Path>>(src: P, dst: Q) -> Result<bool> {
+    DEFAULT_RESOLVER.load().try_can_rename(src, dst)
+}
+
+/// Checks if two paths are related at the given granularity: on the same mount,
+/// filesystem, block device or physical disk.
+///
+/// [`is_same_vol`] answers a fixed question, but different tasks need different ones:
+/// renaming needs the same mount, reflinks need the same filesystem, and IO scheduling
+/// needs to know whether two paths share a physical disk. See [`Granularity`] for how each
+/// level is determined on Windows and Linux.
+///
+/// # Arguments
+/// * `path1` - First path to check
+/// * `path2` - Second path to check
+/// * `granularity` - How closely the paths must be related
+///
+/// # Returns
+/// `true` if both paths are related at the given granularity, `false` otherwise
+/// (including error cases, use [`try_compare`] to tell them apart).
+///
+/// # Example
+/// ```rust
+/// use samevol::{compare, Granularity};
+///
+/// let (path1, path2) = (r"C:\Users\Public", r"D:\Data");
+/// if compare(path1, path2, Granularity::PhysicalDisk) {
+///     println!("Copy sequentially, both paths are on the same disk");
+/// }
+/// ```
+pub fn compare<P: AsRef<Path>, Q: AsRef<Path>>(path1: P, path2: Q, granularity: Granularity) -> bool {
+    DEFAULT_RESOLVER.load().compare(path1, path2, granularity)
+}
+
+/// Checks if two paths are related at the given granularity, reporting why it could not
+/// be determined.
+///
+/// This is the fallible counterpart of [`compare`].
+///
+/// # Errors
+/// - The errors of [`try_resolve_device_path`], if a path cannot be resolved
+/// - [`Error::Metadata`]: The block devices or physical disks of a volume could not be
+///   determined, e.g. because the backend does not support it
+pub fn try_compare<P: AsRef<Path>, Q: AsRef<Path>>(path1: P, path2: Q, granularity: Granularity) -> Result<bool> {
+    DEFAULT_RESOLVER.load().try_compare(path1, path2, granularity)
+}
+
+/// Checks if two paths reside on the same volume, reporting why it could not be
+/// determined.
+///
+/// This is the fallible counterpart of [`is_same_vol`]: `Ok(false)` means the paths are
+/// known to be on different volumes, while `Err(_)` means at least one of them could not
+/// be resolved. See [`try_resolve_device_path`] for the possible errors.
+///
+/// # Example
+/// ```rust
+/// use samevol::try_is_same_vol;
+///
+/// match try_is_same_vol(r"C:\Windows\System32", r"D:\Data\test.txt") {
+///     Ok(same) => println!("Same volume? {}", same),
+///     Err(e) => eprintln!("Couldn't determine: {}", e),
+/// }
+/// ```
+pub fn try_is_same_vol<P: AsRef<Path>, Q: AsRef<Path>>(path1: P, path2: Q) -> Result<bool> {
+    DEFAULT_RESOLVER.load().try_is_same_vol(path1, path2)
 }
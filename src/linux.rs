@@ -0,0 +1,270 @@
+/*
+ * Copyright 2025 爱佐 (Ayrzo)
+ *
+ * This file is part of cargo crate samevol (https://crates.io/crates/samevol),
+ * which licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Linux 平台实现：基于 `/proc/self/mountinfo` 枚举挂载点及其设备号
+
+use std::ffi::{CString, OsStr, OsString};
+use std::io;
+use std::mem::MaybeUninit;
+use std::os::unix::ffi::{OsStrExt as _, OsStringExt as _};
+use std::os::unix::fs::{FileTypeExt as _, MetadataExt as _};
+use std::path::{Component, Path, PathBuf};
+
+use crate::matching::PathMatcher;
+use crate::{VolumeBackend, VolumeInfo, VolumeMetadata, mountinfo};
+
+/// 当前进程挂载表路径
+const MOUNTINFO_PATH: &str = "/proc/self/mountinfo";
+/// 按 `major:minor` 索引的块设备目录
+const SYS_BLOCK_PATH: &str = "/sys/dev/block";
+
+/// Linux volume backend built on `/proc/self/mountinfo`.
+///
+/// Every mounted filesystem is treated as a volume identified by its `major:minor`
+/// device number, so bind mounts of the same filesystem belong to the same volume. Every
+/// visible mount is reported as its own mount point, which lets
+/// [`can_rename`](crate::can_rename) tell mounts of the same volume apart.
+///
+/// Volume metadata reports the filesystem type from `mountinfo` and the capacity from
+/// `statvfs`. Labels and serial numbers are not reported.
+///
+/// Block devices are identified by the `major:minor` number of the device node the
+/// filesystem was mounted from, physical disks by their kernel name (e.g. `sda` or
+/// `nvme0n1`), found by walking `/sys/dev/block` through partitions and stacked devices
+/// such as LVM or software RAID.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LinuxBackend;
+
+impl VolumeBackend for LinuxBackend {
+    fn volumes(&self) -> io::Result<Vec<(String, Vec<OsString>)>> {
+        let content = std::fs::read(MOUNTINFO_PATH)?;
+        let entries = mountinfo::parse(&content)?;
+
+        Ok(mountinfo::volumes(&entries)
+            .into_iter()
+            .map(|(device_id, mount_points)| (device_id, mount_points.into_iter().map(with_trailing_slash).collect()))
+            .collect())
+    }
+
+    fn full_path(&self, path: &Path) -> io::Result<PathBuf> {
+        let absolute = std::path::absolute(path)?;
+        canonicalize_existing_prefix(&absolute)
+    }
+
+    /// Linux 没有 `GetVolumePathNameW` 的等价物，这里原样返回完整路径（以 `/` 结尾），
+    /// 由映射表的最长前缀匹配得到实际挂载点。
+    fn volume_mount_point(&self, full_path: &Path) -> io::Result<OsString> {
+        Ok(with_trailing_slash(full_path.as_os_str().as_encoded_bytes().to_vec()))
+    }
+
+    /// `full_path` 已解析路径中存在的符号链接
+    fn is_link(&self, _full_path: &Path) -> bool {
+        false
+    }
+
+    /// 通过 `statx` 实时查询最近的已存在祖先所在的挂载；内核不支持 `STATX_MNT_ID`（5.8 之前）时改为读取当前挂载表
+    fn mount_id(&self, full_path: &Path) -> io::Result<Option<u64>> {
+        for ancestor in full_path.ancestors() {
+            match statx_mount_id(ancestor) {
+                Ok(Some(mount_id)) => return Ok(Some(mount_id)),
+                Ok(None) => return mountinfo_mount_id(full_path).map(Some),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Err(io::Error::new(io::ErrorKind::NotFound, "no ancestor of the path exists"))
+    }
+
+    /// 挂载源为块设备节点时（如 btrfs 的 `/dev/sda2`）取其设备号，否则卷本身即块设备
+    fn block_device(&self, volume: &VolumeInfo) -> io::Result<String> {
+        let entry = find_mount(volume)?;
+        match std::fs::metadata(&entry.mount_source) {
+            Ok(metadata) if metadata.file_type().is_block_device() => {
+                let rdev = metadata.rdev();
+                Ok(format!("{}:{}", libc::major(rdev), libc::minor(rdev)))
+            }
+            _ => Ok(volume.id().as_str().to_owned()),
+        }
+    }
+
+    /// 通过 `/sys/dev/block` 找到块设备所在的整盘；没有块设备的文件系统（如 tmpfs）不在任何磁盘上
+    fn physical_disks(&self, volume: &VolumeInfo) -> io::Result<Vec<String>> {
+        let block_device = self.block_device(volume)?;
+        let sys_path = Path::new(SYS_BLOCK_PATH).join(&block_device);
+        let dir = match std::fs::canonicalize(&sys_path) {
+            Ok(dir) => dir,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        let mut disks = Vec::new();
+        collect_disks(&dir, &mut disks)?;
+        Ok(disks)
+    }
+
+    fn volume_metadata(&self, volume: &VolumeInfo) -> io::Result<VolumeMetadata> {
+        let entry = find_mount(volume)?;
+        let stats = statvfs(Path::new(OsStr::from_bytes(&entry.mount_point)))?;
+        // 各字段宽度因架构而异（32 位平台上可能为 u32）
+        let block_size = stats.f_frsize as u64;
+        let bytes = |blocks: u64| blocks.saturating_mul(block_size);
+
+        Ok(VolumeMetadata::new().with_fs_type(entry.fs_type.as_str()).with_capacity(
+            bytes(stats.f_blocks as u64),
+            bytes(stats.f_bfree as u64),
+            bytes(stats.f_bavail as u64),
+        ))
+    }
+}
+
+/// 在当前挂载表中查找卷对应的条目：优先选择与已知挂载点一致的条目，其次是该设备最后挂载的条目
+fn find_mount(volume: &VolumeInfo) -> io::Result<mountinfo::MountInfo> {
+    let content = std::fs::read(MOUNTINFO_PATH)?;
+    let entries = mountinfo::parse(&content)?;
+
+    let wanted = volume.mount_points().first().map(|mount_point| {
+        let bytes = mount_point.as_os_str().as_bytes();
+        bytes.strip_suffix(b"/").filter(|bytes| !bytes.is_empty()).unwrap_or(bytes)
+    });
+    let mut candidates = entries.into_iter().rev().filter(|entry| entry.device_id() == volume.id().as_str());
+    match wanted {
+        Some(wanted) => {
+            let mut fallback = None;
+            for entry in candidates {
+                if entry.mount_point == wanted {
+                    return Ok(entry);
+                }
+                fallback.get_or_insert(entry);
+            }
+            fallback
+        }
+        None => candidates.next(),
+    }
+    .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "volume is not mounted"))
+}
+
+/// 在当前挂载表中查找包含路径的最长挂载点，同一位置的多次挂载以最后（最上层）一次为准
+fn mountinfo_mount_id(full_path: &Path) -> io::Result<u64> {
+    let content = std::fs::read(MOUNTINFO_PATH)?;
+    let entries = mountinfo::parse(&content)?;
+
+    let matcher = PathMatcher::unix();
+    entries
+        .iter()
+        .map(|entry| (matcher.key(OsStr::from_bytes(&entry.mount_point)), entry))
+        .filter(|(key, _)| matcher.key(full_path).starts_with(key))
+        .max_by_key(|(key, _)| key.len())
+        .map(|(_, entry)| u64::from(entry.mount_id))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no mount contains the path"))
+}
+
+/// 收集块设备所在的整盘：分区取其父设备，device mapper、md 等虚拟设备递归其下层设备
+fn collect_disks(dir: &Path, disks: &mut Vec<String>) -> io::Result<()> {
+    let dir = match dir.parent() {
+        Some(parent) if dir.join("partition").exists() => parent,
+        _ => dir,
+    };
+
+    let slaves = match std::fs::read_dir(dir.join("slaves")) {
+        Ok(entries) => entries.collect::<io::Result<Vec<_>>>()?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(e),
+    };
+    if slaves.is_empty() {
+        if let Some(name) = dir.file_name().and_then(OsStr::to_str)
+            && !disks.iter().any(|disk| disk == name)
+        {
+            disks.push(name.to_owned());
+        }
+        return Ok(());
+    }
+
+    for slave in slaves {
+        collect_disks(&std::fs::canonicalize(slave.path())?, disks)?;
+    }
+    Ok(())
+}
+
+/// 调用 `statx` 获取路径所在挂载的 ID，内核或 C 库不支持时返回 `None`
+fn statx_mount_id(path: &Path) -> io::Result<Option<u64>> {
+    let path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "path contains NUL"))?;
+    let mut stats = MaybeUninit::<libc::statx>::zeroed();
+
+    // SAFETY: `path` 是以 NUL 结尾的有效字符串，`stats` 指向足够大的可写内存
+    if unsafe { libc::statx(libc::AT_FDCWD, path.as_ptr(), 0, libc::STATX_MNT_ID, stats.as_mut_ptr()) } != 0 {
+        let error = io::Error::last_os_error();
+        return match error.raw_os_error() {
+            Some(libc::ENOSYS) => Ok(None),
+            _ => Err(error),
+        };
+    }
+    // SAFETY: 缓冲区已清零，调用成功时内核已填充其余字段
+    let stats = unsafe { stats.assume_init() };
+    Ok((stats.stx_mask & libc::STATX_MNT_ID != 0).then_some(stats.stx_mnt_id))
+}
+
+/// 调用 `statvfs` 获取文件系统容量信息
+fn statvfs(path: &Path) -> io::Result<libc::statvfs> {
+    let path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "path contains NUL"))?;
+    let mut stats = MaybeUninit::<libc::statvfs>::uninit();
+
+    // SAFETY: `path` 是以 NUL 结尾的有效字符串，`stats` 指向足够大的可写内存
+    if unsafe { libc::statvfs(path.as_ptr(), stats.as_mut_ptr()) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: 调用成功时 `stats` 已被完整初始化
+    Ok(unsafe { stats.assume_init() })
+}
+
+/// 确保路径以斜杠结尾，用于前缀匹配（按字节处理，无需为有效 UTF-8）
+fn with_trailing_slash(mut path: Vec<u8>) -> OsString {
+    if path.last() != Some(&b'/') {
+        path.push(b'/');
+    }
+    OsString::from_vec(path)
+}
+
+/// 规范化路径中已存在的最长前缀，其余不存在的部分按字面拼接
+///
+/// 与 `GetVolumePathNameW` 一致，路径本身无需存在。
+fn canonicalize_existing_prefix(path: &Path) -> io::Result<PathBuf> {
+    for ancestor in path.ancestors() {
+        let base = match std::fs::canonicalize(ancestor) {
+            Ok(base) => base,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+
+        // 拼接剩余部分，按字面处理 `.` 和 `..`
+        let mut result = base;
+        let rest = path.strip_prefix(ancestor).unwrap_or(Path::new(""));
+        for component in rest.components() {
+            match component {
+                Component::ParentDir => {
+                    result.pop();
+                }
+                Component::Normal(name) => result.push(name),
+                _ => {}
+            }
+        }
+        return Ok(result);
+    }
+
+    Err(io::Error::new(io::ErrorKind::NotFound, "No existing ancestor directory"))
+}
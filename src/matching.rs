@@ -0,0 +1,452 @@
+/*
+ * Copyright 2025 爱佐 (Ayrzo)
+ *
+ * This file is part of cargo crate samevol (https://crates.io/crates/samevol),
+ * which licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Matching paths against mount points.
+//!
+//! A [`PathMatcher`] turns paths and mount points into comparison keys, then finds the
+//! longest mount point that is a prefix of a path on a component boundary. The keys
+//! take the path syntax of the platform into account:
+//!
+//! - On Windows, `/` is a separator like `\`, the `\\?\` and `\\.\` prefixes in front of
+//!   a drive letter or `UNC\` are stripped, and names are compared case-insensitively
+//!   like NTFS does.
+//! - On Linux, paths are compared byte for byte.
+//!
+//! Keys of non-empty paths always end with a separator, so `C:\foo\` never matches
+//! `C:\foobar\`.
+//!
+//! # Example
+//! ```rust
+//! use samevol::matching::PathMatcher;
+//!
+//! let matcher = PathMatcher::windows();
+//! let mount_points = [r"C:\", r"C:\Mnt\VHD\", r"C:\Mnt\VHD2\"];
+//! assert_eq!(matcher.longest_match(r"\\?\c:\mnt\vhd\file.txt", mount_points), Some(r"C:\Mnt\VHD\"));
+//! assert_eq!(matcher.longest_match(r"C:\Mnt\VHD2", mount_points), Some(r"C:\Mnt\VHD2\"));
+//! assert_eq!(matcher.longest_match(r"C:\Mnt\VHD22", mount_points), Some(r"C:\"));
+//! ```
+
+use std::cmp::Ordering;
+use std::path::Path;
+
+/// How names are compared by a [`PathMatcher`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+#[non_exhaustive]
+pub enum CaseFolding {
+    /// Names are compared byte for byte, as on Linux file systems.
+    #[default]
+    Exact,
+    /// ASCII letters are compared case-insensitively, other characters exactly.
+    Ascii,
+    /// Names are compared case-insensitively like NTFS does.
+    ///
+    /// NTFS upcases every UTF-16 code unit through the `$UpCase` table of the volume.
+    /// This is approximated with the simple Unicode uppercase mapping of characters in
+    /// the Basic Multilingual Plane: characters whose uppercase form is not a single
+    /// BMP character (such as `ß`), characters outside the BMP and unpaired surrogates
+    /// are left unchanged.
+    Ntfs,
+}
+
+/// Path syntax understood by a [`PathMatcher`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+enum Syntax {
+    Windows,
+    Unix,
+}
+
+/// Matches paths against mount points, see the [module documentation](self).
+///
+/// A resolver uses the matcher of its backend (see
+/// [`VolumeBackend::path_matcher`](crate::VolumeBackend::path_matcher)) unless one is set
+/// with [`ResolverBuilder::path_matcher`](crate::ResolverBuilder::path_matcher).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PathMatcher {
+    syntax: Syntax,
+    case_folding: CaseFolding,
+}
+
+impl PathMatcher {
+    /// Creates a matcher for Windows paths, with [`CaseFolding::Ntfs`].
+    pub const fn windows() -> Self {
+        PathMatcher { syntax: Syntax::Windows, case_folding: CaseFolding::Ntfs }
+    }
+
+    /// Creates a matcher for Unix paths, with [`CaseFolding::Exact`].
+    pub const fn unix() -> Self {
+        PathMatcher { syntax: Syntax::Unix, case_folding: CaseFolding::Exact }
+    }
+
+    /// Creates the matcher for the paths of the current platform.
+    pub const fn system() -> Self {
+        if cfg!(windows) { Self::windows() } else { Self::unix() }
+    }
+
+    /// Changes how names are compared.
+    pub const fn with_case_folding(mut self, case_folding: CaseFolding) -> Self {
+        self.case_folding = case_folding;
+        self
+    }
+
+    /// Returns how names are compared.
+    pub const fn case_folding(&self) -> CaseFolding {
+        self.case_folding
+    }
+
+    /// Returns the comparison key of a path or mount point.
+    ///
+    /// Two paths refer to the same location for this matcher if their keys are equal,
+    /// and a mount point contains a path if its key is a prefix of the key of the path.
+    /// Keys are byte strings in the encoding of [`OsStr::as_encoded_bytes`](std::ffi::OsStr::as_encoded_bytes).
+    pub fn key<P: AsRef<Path>>(&self, path: P) -> Vec<u8> {
+        let path = path.as_ref().as_os_str().as_encoded_bytes();
+        let separator = self.separator();
+
+        // 逐个组件转换大小写并以单个分隔符连接（根组件自身以分隔符结尾）
+        let mut key = Vec::with_capacity(path.len() + 1);
+        for component in self.components(path) {
+            key.extend(self.fold(component));
+            if key.last() != Some(&separator) {
+                key.push(separator);
+            }
+        }
+        key
+    }
+
+    /// Returns `true` if `path` is `mount_point` or lies below it.
+    pub fn contains<M: AsRef<Path>, P: AsRef<Path>>(&self, mount_point: M, path: P) -> bool {
+        self.key(path).starts_with(&self.key(mount_point))
+    }
+
+    /// Returns the longest of `mount_points` containing `path`.
+    pub fn longest_match<P, I, M>(&self, path: P, mount_points: I) -> Option<M>
+    where
+        P: AsRef<Path>,
+        I: IntoIterator<Item = M>,
+        M: AsRef<Path>,
+    {
+        let path = self.key(path);
+        mount_points
+            .into_iter()
+            .map(|mount_point| (self.key(&mount_point), mount_point))
+            .filter(|(key, _)| path.starts_with(key))
+            .max_by_key(|(key, _)| key.len())
+            .map(|(_, mount_point)| mount_point)
+    }
+}
+
+/// 组件级别的匹配原语，供 [`MountIndex`] 在不分配内存的情况下逐组件查找
+impl PathMatcher {
+    /// 是否按 Windows 路径语法匹配
+    pub(crate) const fn is_windows(&self) -> bool {
+        matches!(self.syntax, Syntax::Windows)
+    }
+
+    fn separator(&self) -> u8 {
+        match self.syntax {
+            Syntax::Windows => b'\\',
+            Syntax::Unix => b'/',
+        }
+    }
+
+    /// 将路径拆分为组件：先是根组件（`\\`、`\` 或 `/`，如有），然后是各个非空名称
+    fn components<'a>(&self, path: &'a [u8]) -> Components<'a> {
+        let is_separator = |b: &u8| *b == self.separator() || self.syntax == Syntax::Windows && *b == b'/';
+        let (unc, path) = match self.syntax {
+            Syntax::Windows => strip_device_prefix(path),
+            Syntax::Unix => (false, path),
+        };
+
+        let leading = path.iter().take_while(|b| is_separator(b)).count();
+        let root: Option<&'static [u8]> = match (self.syntax, leading) {
+            (Syntax::Windows, _) if unc => Some(b"\\\\"),
+            (Syntax::Windows, 0) | (Syntax::Unix, 0) => None,
+            (Syntax::Windows, 1) => Some(b"\\"),
+            (Syntax::Windows, _) => Some(b"\\\\"),
+            (Syntax::Unix, _) => Some(b"/"),
+        };
+        Components { root, rest: path, windows: self.syntax == Syntax::Windows }
+    }
+
+    /// 比较已转换的组件 `folded` 与即时转换的组件 `component`
+    fn cmp_folded(&self, folded: &[u8], component: &[u8]) -> Ordering {
+        if self.case_folding == CaseFolding::Exact {
+            return folded.cmp(component);
+        }
+
+        // ASCII 字符直接比较，遇到非 ASCII 字符后改用逐字符转换
+        for (i, &b) in component.iter().enumerate() {
+            if !b.is_ascii() {
+                return folded[i.min(folded.len())..].iter().copied().cmp(self.fold(&component[i..]));
+            }
+            match folded.get(i) {
+                Some(&f) if f == b.to_ascii_uppercase() => {}
+                Some(&f) => return f.cmp(&b.to_ascii_uppercase()),
+                None => return Ordering::Less,
+            }
+        }
+        folded.len().cmp(&component.len())
+    }
+
+    /// 按大小写规则逐字节转换组件
+    fn fold<'a>(&self, component: &'a [u8]) -> Fold<'a> {
+        Fold { bytes: component, case_folding: self.case_folding, pending: [0; 4], pending_pos: 0, pending_len: 0 }
+    }
+}
+
+/// 路径组件迭代器，见 [`PathMatcher::components`]
+struct Components<'a> {
+    root: Option<&'static [u8]>,
+    rest: &'a [u8],
+    windows: bool,
+}
+
+impl<'a> Iterator for Components<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(root) = self.root.take() {
+            return Some(root);
+        }
+
+        let windows = self.windows;
+        let is_separator = |b: &u8| *b == b'/' || windows && *b == b'\\';
+        let start = self.rest.iter().position(|b| !is_separator(b))?;
+        let rest = &self.rest[start..];
+        let end = rest.iter().position(is_separator).unwrap_or(rest.len());
+        self.rest = &rest[end..];
+        Some(&rest[..end])
+    }
+}
+
+/// 大小写转换后的字节迭代器，见 [`PathMatcher::fold`]
+struct Fold<'a> {
+    bytes: &'a [u8],
+    case_folding: CaseFolding,
+    /// 已转换但尚未输出的多字节字符
+    pending: [u8; 4],
+    pending_pos: u8,
+    pending_len: u8,
+}
+
+impl Iterator for Fold<'_> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.pending_pos < self.pending_len {
+            self.pending_pos += 1;
+            return Some(self.pending[self.pending_pos as usize - 1]);
+        }
+
+        let (&b, rest) = self.bytes.split_first()?;
+        match self.case_folding {
+            CaseFolding::Exact => {}
+            CaseFolding::Ascii | CaseFolding::Ntfs if b.is_ascii() => {
+                self.bytes = rest;
+                return Some(b.to_ascii_uppercase());
+            }
+            CaseFolding::Ascii => {}
+            CaseFolding::Ntfs => {
+                if let Some((c, width)) = ntfs_upcase(self.bytes) {
+                    let len = c.encode_utf8(&mut self.pending).len();
+                    self.bytes = &self.bytes[width..];
+                    self.pending_pos = 1;
+                    self.pending_len = len as u8;
+                    return Some(self.pending[0]);
+                }
+            }
+        }
+        self.bytes = rest;
+        Some(b)
+    }
+}
+
+impl Default for PathMatcher {
+    /// Returns [`PathMatcher::system`].
+    fn default() -> Self {
+        Self::system()
+    }
+}
+
+/// An index of mount points, answering longest-prefix lookups in time proportional to
+/// the depth of the path.
+///
+/// Mount points are stored in a trie with one level per path component, compared
+/// according to a [`PathMatcher`]. Lookups walk the components of the path and compare
+/// them with the stored ones on the fly, without allocating.
+///
+/// # Example
+/// ```rust
+/// use samevol::matching::{MountIndex, PathMatcher};
+///
+/// let mut index = MountIndex::new(PathMatcher::windows());
+/// index.insert(r"C:\", "system");
+/// index.insert(r"C:\Mnt\VHD\", "vhd");
+///
+/// assert_eq!(index.longest_match(r"c:\mnt\vhd\file.txt"), Some(&"vhd"));
+/// assert_eq!(index.longest_match(r"C:\Mnt\VHD2"), Some(&"system"));
+/// assert_eq!(index.longest_match(r"D:\"), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MountIndex<T> {
+    matcher: PathMatcher,
+    root: Node<T>,
+    len: usize,
+}
+
+/// 前缀树节点，子节点按转换后的组件排序以便二分查找
+#[derive(Debug, Clone)]
+struct Node<T> {
+    value: Option<T>,
+    children: Vec<(Box<[u8]>, Node<T>)>,
+}
+
+impl<T> Default for Node<T> {
+    fn default() -> Self {
+        Node { value: None, children: Vec::new() }
+    }
+}
+
+impl<T> MountIndex<T> {
+    /// Creates an empty index comparing paths with `matcher`.
+    pub fn new(matcher: PathMatcher) -> Self {
+        MountIndex { matcher, root: Node::default(), len: 0 }
+    }
+
+    /// Returns the matcher used to compare paths.
+    pub fn matcher(&self) -> PathMatcher {
+        self.matcher
+    }
+
+    /// Returns the number of mount points in the index.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the index contains no mount point.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Adds a mount point, returning the value previously stored for an equivalent
+    /// mount point, if any.
+    pub fn insert<M: AsRef<Path>>(&mut self, mount_point: M, value: T) -> Option<T> {
+        let matcher = self.matcher;
+        let mut node = &mut self.root;
+        for component in matcher.components(mount_point.as_ref().as_os_str().as_encoded_bytes()) {
+            let folded: Box<[u8]> = matcher.fold(component).collect();
+            let index = match node.children.binary_search_by(|(key, _)| key.cmp(&folded)) {
+                Ok(index) => index,
+                Err(index) => {
+                    node.children.insert(index, (folded, Node::default()));
+                    index
+                }
+            };
+            node = &mut node.children[index].1;
+        }
+
+        let previous = node.value.replace(value);
+        if previous.is_none() {
+            self.len += 1;
+        }
+        previous
+    }
+
+    /// Returns the value stored for the mount point equivalent to `path`, if any.
+    pub fn get<P: AsRef<Path>>(&self, path: P) -> Option<&T> {
+        let mut node = &self.root;
+        for component in self.matcher.components(path.as_ref().as_os_str().as_encoded_bytes()) {
+            let index = node
+                .children
+                .binary_search_by(|(key, _)| self.matcher.cmp_folded(key, component))
+                .ok()?;
+            node = &node.children[index].1;
+        }
+        node.value.as_ref()
+    }
+
+    /// Returns the value of the longest mount point containing `path`.
+    pub fn longest_match<P: AsRef<Path>>(&self, path: P) -> Option<&T> {
+        let mut node = &self.root;
+        let mut best = node.value.as_ref();
+        for component in self.matcher.components(path.as_ref().as_os_str().as_encoded_bytes()) {
+            // 比较已转换的键和即时转换的组件，无需分配内存
+            let found = node
+                .children
+                .binary_search_by(|(key, _)| self.matcher.cmp_folded(key, component));
+            match found {
+                Ok(index) => node = &node.children[index].1,
+                Err(_) => break,
+            }
+            best = node.value.as_ref().or(best);
+        }
+        best
+    }
+}
+
+impl<T> Default for MountIndex<T> {
+    /// Creates an empty index using [`PathMatcher::system`].
+    fn default() -> Self {
+        Self::new(PathMatcher::default())
+    }
+}
+
+/// 去除驱动器号或 `UNC\` 之前的 `\\?\`、`\\.\` 前缀
+///
+/// 返回是否为 UNC 路径（`UNC\` 部分也被去除）和剩余部分。
+fn strip_device_prefix(path: &[u8]) -> (bool, &[u8]) {
+    let is_separator = |b: u8| b == b'\\' || b == b'/';
+    let is_prefix = path.len() >= 4
+        && is_separator(path[0])
+        && is_separator(path[1])
+        && matches!(path[2], b'?' | b'.')
+        && is_separator(path[3]);
+    if !is_prefix {
+        return (false, path);
+    }
+
+    let rest = &path[4..];
+    if rest.len() >= 2 && rest[0].is_ascii_alphabetic() && rest[1] == b':' {
+        (false, rest)
+    } else if rest.len() >= 4 && rest[..3].eq_ignore_ascii_case(b"UNC") && is_separator(rest[3]) {
+        (true, &rest[4..])
+    } else {
+        (false, path)
+    }
+}
+
+/// 按 NTFS 规则转换开头的非 ASCII 字符，返回转换结果和原字符的字节数
+///
+/// 只转换 BMP 字符；无效序列（如未配对的代理项）和 BMP 之外的字符返回 `None`，原样保留。
+fn ntfs_upcase(bytes: &[u8]) -> Option<(char, usize)> {
+    let width = match bytes.first()? {
+        0xC2..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        _ => return None, // 4 字节序列位于 BMP 之外，不转换
+    };
+    let c = std::str::from_utf8(bytes.get(..width)?).ok()?.chars().next()?;
+
+    let mut upper = c.to_uppercase();
+    let c = match (upper.next(), upper.next()) {
+        (Some(u), None) if (u as u32) <= 0xFFFF => u,
+        _ => c,
+    };
+    Some((c, width))
+}
@@ -0,0 +1,258 @@
+/*
+ * Copyright 2025 爱佐 (Ayrzo)
+ *
+ * This file is part of cargo crate samevol (https://crates.io/crates/samevol),
+ * which licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Parser for the Linux `/proc/<pid>/mountinfo` format.
+//!
+//! The parser is pure Rust and does not touch the file system, so it can be used
+//! on any platform, e.g. to inspect a mount table captured on another machine.
+//!
+//! See [`proc_pid_mountinfo(5)`](https://man7.org/linux/man-pages/man5/proc_pid_mountinfo.5.html)
+//! for the description of each field.
+
+use std::collections::HashMap;
+use std::io;
+
+/// A single line of a `mountinfo` file.
+///
+/// Path-like fields (`root` and `mount_point`) are kept as raw bytes with the kernel's
+/// octal escapes (`\040`, `\011`, `\012`, `\134`) already decoded, because Linux paths
+/// are not required to be valid UTF-8.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MountInfo {
+    /// Unique ID of the mount.
+    pub mount_id: u32,
+    /// ID of the parent mount (or of self for the root of this mount namespace's tree).
+    pub parent_id: u32,
+    /// Major device number of the filesystem (`st_dev` major).
+    pub major: u32,
+    /// Minor device number of the filesystem (`st_dev` minor).
+    pub minor: u32,
+    /// Path of the directory in the filesystem which forms the root of this mount.
+    pub root: Vec<u8>,
+    /// Path of the mount point relative to the process's root directory.
+    pub mount_point: Vec<u8>,
+    /// Per-mount options, e.g. `rw,relatime`.
+    pub mount_options: String,
+    /// Optional fields such as `shared:1` or `master:2`.
+    pub optional_fields: Vec<String>,
+    /// Filesystem type, e.g. `ext4` or `btrfs`.
+    pub fs_type: String,
+    /// Filesystem-specific mount source, e.g. `/dev/sda1`, or `none`.
+    pub mount_source: String,
+    /// Per-superblock options.
+    pub super_options: String,
+}
+
+impl MountInfo {
+    /// Returns the `major:minor` device number, which identifies the filesystem.
+    ///
+    /// # Example
+    /// ```rust
+    /// use samevol::mountinfo;
+    ///
+    /// let line = b"36 35 98:0 /mnt1 /mnt/parent rw,noatime master:1 - ext3 /dev/root rw,errors=continue";
+    /// let entry = mountinfo::parse_line(line).unwrap();
+    /// assert_eq!(entry.device_id(), "98:0");
+    /// ```
+    pub fn device_id(&self) -> String {
+        format!("{}:{}", self.major, self.minor)
+    }
+}
+
+/// Parses the whole content of a `mountinfo` file.
+///
+/// Empty lines are ignored.
+///
+/// # Errors
+/// Returns an [`io::ErrorKind::InvalidData`] error naming the offending line number
+/// if any line is malformed.
+///
+/// # Example
+/// ```rust
+/// use samevol::mountinfo;
+///
+/// let content = b"22 1 8:1 / / rw,relatime shared:1 - ext4 /dev/sda1 rw\n\
+///                 23 22 0:21 / /proc rw,nosuid shared:12 - proc proc rw\n";
+/// let entries = mountinfo::parse(content).unwrap();
+/// assert_eq!(entries.len(), 2);
+/// assert_eq!(entries[1].mount_point, b"/proc");
+/// ```
+pub fn parse(input: &[u8]) -> io::Result<Vec<MountInfo>> {
+    input
+        .split(|&b| b == b'\n')
+        .enumerate()
+        .filter(|(_, line)| !line.iter().all(u8::is_ascii_whitespace))
+        .map(|(i, line)| {
+            parse_line(line).map_err(|e| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("mountinfo line {}: {}", i + 1, e))
+            })
+        })
+        .collect()
+}
+
+/// Groups the visible mounts of a parsed `mountinfo` file by device number.
+///
+/// This is the volume table a Linux resolver works with: one entry per `major:minor`
+/// device number, in the order of its first visible mount, with the mount points of the
+/// device. A mount point that was mounted over is only reported for the last mount, as
+/// only that one is visible.
+///
+/// # Example
+/// ```rust
+/// use samevol::mountinfo;
+///
+/// let content = b"22 1 8:1 / / rw - ext4 /dev/sda1 rw\n\
+///                 30 22 8:17 / /home rw - ext4 /dev/sdb1 rw\n\
+///                 31 22 8:1 /srv /srv rw - ext4 /dev/sda1 rw\n\
+///                 32 30 0:40 / /home rw - tmpfs tmpfs rw\n";
+/// let volumes = mountinfo::volumes(&mountinfo::parse(content).unwrap());
+/// assert_eq!(volumes.len(), 2);
+/// assert_eq!(volumes[0], ("8:1".to_owned(), vec![b"/".to_vec(), b"/srv".to_vec()]));
+/// assert_eq!(volumes[1], ("0:40".to_owned(), vec![b"/home".to_vec()]));
+/// ```
+pub fn volumes(entries: &[MountInfo]) -> Vec<(String, Vec<Vec<u8>>)> {
+    // mountinfo 按挂载顺序排列，同一挂载点被重复挂载时只有最后一个可见
+    let mut visible = HashMap::new();
+    for (index, entry) in entries.iter().enumerate() {
+        visible.insert(entry.mount_point.as_slice(), index);
+    }
+
+    // 按设备号分组，保持首次出现的顺序
+    let mut volumes: Vec<(String, Vec<Vec<u8>>)> = Vec::new();
+    for (index, entry) in entries.iter().enumerate() {
+        if visible.get(entry.mount_point.as_slice()) != Some(&index) {
+            continue;
+        }
+
+        let device_id = entry.device_id();
+        match volumes.iter_mut().find(|(id, _)| *id == device_id) {
+            Some((_, mount_points)) => mount_points.push(entry.mount_point.clone()),
+            None => volumes.push((device_id, vec![entry.mount_point.clone()])),
+        }
+    }
+    volumes
+}
+
+/// Parses a single `mountinfo` line (without the trailing newline).
+///
+/// # Errors
+/// Returns an [`io::ErrorKind::InvalidData`] error if the line is malformed.
+pub fn parse_line(line: &[u8]) -> io::Result<MountInfo> {
+    let mut fields = line.split(|&b| b == b' ').filter(|f| !f.is_empty());
+    let mut next = |name: &str| {
+        fields
+            .next()
+            .ok_or_else(|| invalid_data(format!("missing field `{}`", name)))
+    };
+
+    let mount_id = parse_number(next("mount ID")?, "mount ID")?;
+    let parent_id = parse_number(next("parent ID")?, "parent ID")?;
+    let (major, minor) = parse_device(next("major:minor")?)?;
+    let root = unescape(next("root")?);
+    let mount_point = unescape(next("mount point")?);
+    let mount_options = lossy(next("mount options")?);
+
+    // 可选字段数量不定，以单独的 `-` 作为结束标记
+    let mut optional_fields = Vec::new();
+    loop {
+        let field = next("separator `-`")?;
+        if field == b"-" {
+            break;
+        }
+        optional_fields.push(lossy(field));
+    }
+
+    let fs_type = lossy(next("filesystem type")?);
+    let mount_source = lossy(next("mount source")?);
+    // 部分旧内核的超级块选项可能缺失，按空字符串处理
+    let super_options = fields.next().map(lossy).unwrap_or_default();
+
+    Ok(MountInfo {
+        mount_id,
+        parent_id,
+        major,
+        minor,
+        root,
+        mount_point,
+        mount_options,
+        optional_fields,
+        fs_type,
+        mount_source,
+        super_options,
+    })
+}
+
+/// Decodes the octal escapes (`\NNN`) the kernel uses for space, tab, newline and backslash.
+///
+/// Malformed escape sequences are kept verbatim.
+///
+/// # Example
+/// ```rust
+/// use samevol::mountinfo::unescape;
+///
+/// assert_eq!(unescape(br"/mnt/my\040disk"), b"/mnt/my disk");
+/// ```
+pub fn unescape(field: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(field.len());
+    let mut i = 0;
+    while i < field.len() {
+        if field[i] == b'\\' && i + 4 <= field.len() {
+            let digits = &field[i + 1..i + 4];
+            if digits.iter().all(|d| (b'0'..=b'7').contains(d)) {
+                let value = digits.iter().fold(0u32, |acc, d| acc * 8 + (d - b'0') as u32);
+                if let Ok(byte) = u8::try_from(value) {
+                    result.push(byte);
+                    i += 4;
+                    continue;
+                }
+            }
+        }
+        result.push(field[i]);
+        i += 1;
+    }
+    result
+}
+
+/// 解析十进制数字字段
+fn parse_number(field: &[u8], name: &str) -> io::Result<u32> {
+    std::str::from_utf8(field)
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| invalid_data(format!("invalid {}: `{}`", name, String::from_utf8_lossy(field))))
+}
+
+/// 解析 `major:minor` 设备号字段
+fn parse_device(field: &[u8]) -> io::Result<(u32, u32)> {
+    let colon = field
+        .iter()
+        .position(|&b| b == b':')
+        .ok_or_else(|| invalid_data(format!("invalid major:minor: `{}`", String::from_utf8_lossy(field))))?;
+    Ok((
+        parse_number(&field[..colon], "major")?,
+        parse_number(&field[colon + 1..], "minor")?,
+    ))
+}
+
+/// 解码转义后按 UTF-8 转换（非法字节以替换字符表示）
+fn lossy(field: &[u8]) -> String {
+    String::from_utf8_lossy(&unescape(field)).into_owned()
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,97 @@
+/*
+ * Copyright 2025 爱佐 (Ayrzo)
+ *
+ * This file is part of cargo crate samevol (https://crates.io/crates/samevol),
+ * which licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Parser for the output of the Windows `mountvol` command.
+//!
+//! Run without arguments, `mountvol` prints its usage, followed by every volume GUID
+//! path with the mount points of the volume indented below it:
+//!
+//! ```text
+//! Possible values for VolumeName along with current mount points are:
+//!
+//!     \\?\Volume{e8a7f3c2-0000-0000-0000-100000000000}\
+//!         C:\
+//!
+//!     \\?\Volume{e8a7f3c2-0000-0000-0000-200000000000}\
+//!         *** NO MOUNT POINTS ***
+//! ```
+//!
+//! The parser is pure Rust, so output captured on a Windows machine can be inspected
+//! on any platform. It does not depend on the language of the usage text: everything
+//! before the first volume GUID path is skipped.
+
+use std::io;
+
+use crate::guid::VolumeGuidPath;
+
+/// 卷 GUID 路径的前缀（不区分大小写），用于识别损坏的卷行
+const VOLUME_PREFIX: &str = r"\\?\Volume";
+
+/// Parses the output of `mountvol` into the volumes and their mount points.
+///
+/// Returns one entry per volume, in the order printed, with the volume GUID path as
+/// printed and its mount points. Volumes listed without mount points (`*** NO MOUNT
+/// POINTS ***` or its translation) get an empty list.
+///
+/// # Errors
+/// Returns an [`io::ErrorKind::InvalidData`] error if the output does not list any
+/// volume, or contains a malformed volume GUID path (e.g. a truncated line).
+///
+/// # Example
+/// ```rust
+/// use samevol::mountvol;
+///
+/// let output = r"
+///     \\?\Volume{e8a7f3c2-0000-0000-0000-100000000000}\
+///         C:\
+///         D:\Mnt\Data\
+///
+///     \\?\Volume{e8a7f3c2-0000-0000-0000-200000000000}\
+///         *** NO MOUNT POINTS ***
+/// ";
+/// let volumes = mountvol::parse(output).unwrap();
+/// assert_eq!(volumes.len(), 2);
+/// assert_eq!(volumes[0].1, [r"C:\", r"D:\Mnt\Data\"]);
+/// assert!(volumes[1].1.is_empty());
+/// ```
+pub fn parse(output: &str) -> io::Result<Vec<(String, Vec<String>)>> {
+    let mut volumes: Vec<(String, Vec<String>)> = Vec::new();
+
+    for line in output.lines() {
+        let line = line.trim();
+        if VolumeGuidPath::parse(line).is_ok() {
+            volumes.push((line.to_owned(), Vec::new()));
+            continue;
+        }
+        // 截断或损坏的卷 GUID 路径不能当作上一个卷的挂载点
+        if line.get(..VOLUME_PREFIX.len()).is_some_and(|prefix| prefix.eq_ignore_ascii_case(VOLUME_PREFIX)) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("malformed volume GUID path `{}`", line)));
+        }
+
+        // 首个卷之前是用法说明；`*** ... ***` 表示卷没有挂载点（文字随系统语言而变）
+        let Some((_, mount_points)) = volumes.last_mut() else { continue };
+        if line.is_empty() || line.starts_with("***") {
+            continue;
+        }
+        mount_points.push(line.to_owned());
+    }
+
+    if volumes.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "mountvol output does not list any volume"));
+    }
+    Ok(volumes)
+}
@@ -0,0 +1,167 @@
+/*
+ * Copyright 2025 爱佐 (Ayrzo)
+ *
+ * This file is part of cargo crate samevol (https://crates.io/crates/samevol),
+ * which licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! 基于捕获的挂载表离线解析路径的后端
+
+use std::ffi::OsString;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::matching::PathMatcher;
+use crate::{FakeBackend, VolumeBackend, Volumes, mountinfo, mountvol};
+
+/// A [`VolumeBackend`] answering purely from a captured mount table.
+///
+/// Use it to replay the decisions of a resolver on another machine: build it from a
+/// serialized [`Volumes`] snapshot, from the text of a Linux `mountinfo` file or from
+/// the output of the Windows `mountvol` command, and install it in a [`Resolver`]. No
+/// operating system call is made, so the results are the same on every host.
+///
+/// Paths are handled lexically, like with [`FakeBackend`]: relative paths are joined to
+/// the current directory (`C:\` or `/` unless set with
+/// [`with_current_dir`](Self::with_current_dir)), and symbolic links are not followed,
+/// since they cannot be known from the mount table. Filesystem metadata and physical
+/// disks are not available.
+///
+/// [`Resolver`]: crate::Resolver
+///
+/// # Example
+/// ```rust
+/// use samevol::{OfflineBackend, Resolver};
+///
+/// let mountinfo = b"22 1 8:1 / / rw,relatime shared:1 - ext4 /dev/sda1 rw\n\
+///                   30 22 8:17 / /home rw,relatime shared:2 - ext4 /dev/sdb1 rw\n";
+/// let resolver = Resolver::builder().backend(OfflineBackend::from_mountinfo(mountinfo)?).build();
+///
+/// assert_eq!(resolver.resolve_device_path("/home/user/file").unwrap(), "8:17");
+/// assert!(!resolver.is_same_vol("/etc/hosts", "/home/user/file"));
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct OfflineBackend {
+    /// 按字面展开路径并查找挂载点，不注入任何故障
+    layout: FakeBackend,
+}
+
+impl OfflineBackend {
+    /// Creates a backend without volumes, comparing paths with `matcher`.
+    ///
+    /// The path syntax (Windows or Unix) follows the matcher. Add volumes with
+    /// [`with_volume`](Self::with_volume).
+    pub fn new(matcher: PathMatcher) -> Self {
+        OfflineBackend { layout: FakeBackend::with_path_matcher(matcher) }
+    }
+
+    /// Adds the volume `device_path` with its mount points.
+    pub fn with_volume<I, P>(mut self, device_path: &str, mount_points: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        self.layout = self.layout.with_volume(device_path, mount_points);
+        self
+    }
+
+    /// Sets the directory relative paths are resolved against.
+    pub fn with_current_dir<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        self.layout = self.layout.with_current_dir(dir);
+        self
+    }
+
+    /// Creates a backend from a snapshot of the volumes of a resolver, e.g. one loaded
+    /// with [`Volumes::from_json`] (requires the `serde` feature).
+    ///
+    /// The path syntax and matching rules are those the snapshot was taken with.
+    pub fn from_volumes(volumes: &Volumes) -> Self {
+        volumes
+            .iter()
+            .fold(Self::new(volumes.path_matcher()), |backend, volume| {
+                backend.with_volume(volume.id().as_str(), volume.mount_points())
+            })
+    }
+
+    /// Creates a backend from the content of a Linux `/proc/<pid>/mountinfo` file.
+    ///
+    /// Volumes are identified by their `major:minor` device number, as with the
+    /// `LinuxBackend`.
+    ///
+    /// # Errors
+    /// Returns an [`io::ErrorKind::InvalidData`] error if the content is malformed, see
+    /// [`mountinfo::parse`].
+    pub fn from_mountinfo(content: &[u8]) -> io::Result<Self> {
+        let entries = mountinfo::parse(content)?;
+        Ok(mountinfo::volumes(&entries)
+            .into_iter()
+            .fold(Self::new(PathMatcher::unix()), |backend, (device_id, mount_points)| {
+                backend.with_volume(&device_id, mount_points.into_iter().map(bytes_to_path))
+            }))
+    }
+
+    /// Creates a backend from the output of the Windows `mountvol` command.
+    ///
+    /// Volumes are identified by their volume GUID path, as with the
+    /// `WindowsBackend`.
+    ///
+    /// # Errors
+    /// Returns an [`io::ErrorKind::InvalidData`] error if the output does not list any
+    /// volume, see [`mountvol::parse`].
+    pub fn from_mountvol(output: &str) -> io::Result<Self> {
+        Ok(mountvol::parse(output)?
+            .into_iter()
+            .fold(Self::new(PathMatcher::windows()), |backend, (device_path, mount_points)| {
+                backend.with_volume(&device_path, mount_points)
+            }))
+    }
+}
+
+impl VolumeBackend for OfflineBackend {
+    fn volumes(&self) -> io::Result<Vec<(String, Vec<OsString>)>> {
+        self.layout.volumes()
+    }
+
+    fn full_path(&self, path: &Path) -> io::Result<PathBuf> {
+        self.layout.full_path(path)
+    }
+
+    fn volume_mount_point(&self, full_path: &Path) -> io::Result<OsString> {
+        self.layout.volume_mount_point(full_path)
+    }
+
+    fn is_link(&self, full_path: &Path) -> bool {
+        self.layout.is_link(full_path)
+    }
+
+    fn mount_id(&self, full_path: &Path) -> io::Result<Option<u64>> {
+        self.layout.mount_id(full_path)
+    }
+
+    fn path_matcher(&self) -> PathMatcher {
+        self.layout.path_matcher()
+    }
+
+    fn renames_across_mount_points(&self) -> bool {
+        self.layout.renames_across_mount_points()
+    }
+}
+
+/// 将 Linux 路径字节转换为路径；在非 Unix 平台上按 UTF-8 有损转换
+fn bytes_to_path(bytes: Vec<u8>) -> PathBuf {
+    #[cfg(unix)]
+    return PathBuf::from(<OsString as std::os::unix::ffi::OsStringExt>::from_vec(bytes));
+    #[cfg(not(unix))]
+    return PathBuf::from(String::from_utf8_lossy(&bytes).into_owned());
+}
@@ -0,0 +1,169 @@
+/*
+ * Copyright 2025 爱佐 (Ayrzo)
+ *
+ * This file is part of cargo crate samevol (https://crates.io/crates/samevol),
+ * which licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Parsers for the JSON output of the PowerShell storage cmdlets.
+//!
+//! They turn the output of `Get-Volume | ConvertTo-Json` and
+//! `Get-Partition | ConvertTo-Json` into the volume table a Windows resolver works with:
+//! one entry per volume GUID path, with the mount points of the volume. Like
+//! [`mountvol`](crate::mountvol), the parsers are pure Rust and work on any platform,
+//! e.g. to build an [`OfflineBackend`](crate::OfflineBackend) from data captured on a
+//! customer machine.
+//!
+//! `Get-Volume` only reports drive letters. Use `Get-Partition`, whose `AccessPaths`
+//! include folder mount points, to capture the complete layout.
+//!
+//! Windows PowerShell 5.1 writes UTF-16 when redirecting with `>`; capture the output
+//! with `Out-File -Encoding utf8` or `Set-Content -Encoding utf8` instead, or decode it
+//! before parsing. A leading byte order mark is ignored.
+//!
+//! # Example
+//! ```rust
+//! use samevol::matching::PathMatcher;
+//! use samevol::{OfflineBackend, Resolver, powershell};
+//!
+//! let json = r#"[
+//!     { "DiskNumber": 0, "DriveLetter": "C", "AccessPaths": ["C:\\", "\\\\?\\Volume{5e1f9c7a-2b4d-4c6e-8a13-9d0b7e2f4c02}\\"] },
+//!     { "DiskNumber": 1, "DriveLetter": "\u0000", "AccessPaths": ["C:\\Data\\", "\\\\?\\Volume{d27b4f8e-9a1c-4e3d-a6f5-7b8c2e9d0f04}\\"] }
+//! ]"#;
+//!
+//! let mut backend = OfflineBackend::new(PathMatcher::windows());
+//! for (device_path, mount_points) in powershell::parse_partitions(json)? {
+//!     backend = backend.with_volume(&device_path, mount_points);
+//! }
+//! let resolver = Resolver::builder().backend(backend).build();
+//! assert!(!resolver.is_same_vol(r"C:\Windows", r"C:\Data\file"));
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use std::io;
+
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+use crate::guid::VolumeGuidPath;
+
+/// `ConvertTo-Json` 对单个对象不输出数组
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrMany<T> {
+    Many(Vec<T>),
+    One(T),
+}
+
+/// 驱动器号：PowerShell 版本不同，`[char]` 可能序列化为字符串或字符编码
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DriveLetter {
+    Char(char),
+    Code(u32),
+}
+
+impl DriveLetter {
+    /// 转换为根目录形式（`C:\`）；没有驱动器号时为 `\0`
+    fn root(&self) -> Option<String> {
+        let letter = match *self {
+            DriveLetter::Char(c) => c,
+            DriveLetter::Code(code) => char::from_u32(code)?,
+        };
+        letter.is_ascii_alphabetic().then(|| format!(r"{}:\", letter.to_ascii_uppercase()))
+    }
+}
+
+/// `Get-Volume` 输出中用到的属性（`MSFT_Volume`）
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Volume {
+    path: Option<String>,
+    unique_id: Option<String>,
+    drive_letter: Option<DriveLetter>,
+}
+
+/// `Get-Partition` 输出中用到的属性（`MSFT_Partition`）
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Partition {
+    access_paths: Option<Vec<String>>,
+    drive_letter: Option<DriveLetter>,
+}
+
+/// Parses the output of `Get-Volume | ConvertTo-Json` into the volumes and their drive
+/// letters.
+///
+/// Returns one entry per volume with a volume GUID path (taken from `Path`, or from
+/// `UniqueId`), in the order listed, with the root of its drive letter (e.g. `C:\`) if
+/// it has one. Other volumes, such as those of network or removable devices without a
+/// volume GUID path, are skipped.
+///
+/// # Errors
+/// Returns an [`io::ErrorKind::InvalidData`] error if the input is not the JSON of one
+/// or several volumes.
+pub fn parse_volumes(json: &str) -> io::Result<Vec<(String, Vec<String>)>> {
+    Ok(parse_objects::<Volume>(json)?
+        .into_iter()
+        .filter_map(|volume| {
+            let device_path = [volume.path, volume.unique_id].into_iter().flatten().find(|path| is_volume_path(path))?;
+            let mount_points = volume.drive_letter.and_then(|letter| letter.root()).into_iter().collect();
+            Some((device_path, mount_points))
+        })
+        .collect())
+}
+
+/// Parses the output of `Get-Partition | ConvertTo-Json` into the volumes and their
+/// mount points.
+///
+/// Returns one entry per partition holding a volume, in the order listed: the volume
+/// GUID path found in `AccessPaths`, with the other access paths (drive letter roots
+/// and folder mount points) as mount points. Partitions without a volume, such as the
+/// Microsoft reserved partition, are skipped.
+///
+/// # Errors
+/// Returns an [`io::ErrorKind::InvalidData`] error if the input is not the JSON of one
+/// or several partitions.
+pub fn parse_partitions(json: &str) -> io::Result<Vec<(String, Vec<String>)>> {
+    Ok(parse_objects::<Partition>(json)?
+        .into_iter()
+        .filter_map(|partition| {
+            let (device_paths, mut mount_points): (Vec<String>, Vec<String>) =
+                partition.access_paths.unwrap_or_default().into_iter().partition(|path| is_volume_path(path));
+            let device_path = device_paths.into_iter().next()?;
+
+            // `AccessPaths` 通常已包含驱动器号，缺失时补上
+            if let Some(root) = partition.drive_letter.and_then(|letter| letter.root())
+                && !mount_points.iter().any(|mount_point| mount_point.eq_ignore_ascii_case(&root))
+            {
+                mount_points.insert(0, root);
+            }
+            Some((device_path, mount_points))
+        })
+        .collect())
+}
+
+/// 解析单个对象或对象数组，忽略开头的字节顺序标记
+fn parse_objects<T: DeserializeOwned>(json: &str) -> io::Result<Vec<T>> {
+    let json = json.strip_prefix('\u{feff}').unwrap_or(json);
+    match serde_json::from_str(json) {
+        Ok(OneOrMany::Many(objects)) => Ok(objects),
+        Ok(OneOrMany::One(object)) => Ok(vec![object]),
+        Err(e) => Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+    }
+}
+
+/// 判断是否为格式正确的卷 GUID 路径
+fn is_volume_path(path: &str) -> bool {
+    VolumeGuidPath::parse(path).is_ok()
+}
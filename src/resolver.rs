@@ -0,0 +1,936 @@
+/*
+ * Copyright 2025 爱佐 (Ayrzo)
+ *
+ * This file is part of cargo crate samevol (https://crates.io/crates/samevol),
+ * which licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! 基于实例的卷解析器，每个实例拥有独立的后端、刷新策略和卷映射表
+
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, TryLockError};
+use std::time::{Duration, Instant};
+
+use arc_swap::ArcSwapOption;
+
+use crate::matching::{MountIndex, PathMatcher};
+use crate::{
+    DevicePath, Error, MountPoint, Resolution, Result, SystemBackend, VolumeBackend, VolumeId, VolumeInfo,
+    VolumeMetadata,
+};
+
+/// When a [`Resolver`] rebuilds its volume mapping table on its own.
+///
+/// The table is always built on first use, and can always be rebuilt explicitly with
+/// [`Resolver::reinitialize`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RefreshPolicy {
+    /// Never rebuild automatically.
+    #[default]
+    Manual,
+    /// Rebuild when a path does not match any known mount point, then retry once.
+    ///
+    /// Useful when drives are attached at runtime, at the cost of one rebuild per
+    /// unmatched lookup.
+    OnMiss,
+    /// Rebuild on the next lookup once the table is older than the given duration.
+    Interval(Duration),
+}
+
+/// How closely two paths must be related to be considered the same by
+/// [`compare`](crate::compare).
+///
+/// The levels are ordered from the finest to the coarsest: paths on the same mount are
+/// always on the same filesystem, which is always on the same block device, which is
+/// always on the same physical disk.
+///
+/// | Granularity | Question it answers | Windows | Linux |
+/// |---|---|---|---|
+/// | [`Mount`](Granularity::Mount) | Will `rename` work? | volume | mount ID |
+/// | [`Filesystem`](Granularity::Filesystem) | Will reflinks work? | volume | device number |
+/// | [`BlockDevice`](Granularity::BlockDevice) | Same partition or logical volume? | volume | backing device node |
+/// | [`PhysicalDisk`](Granularity::PhysicalDisk) | Will IO contend for the same disk? | disk extents | sysfs disk |
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+#[non_exhaustive]
+pub enum Granularity {
+    /// The same mount, so that a rename between the paths works.
+    ///
+    /// See [`can_rename`](crate::can_rename) for the difference to a volume.
+    Mount,
+    /// The same filesystem (volume), as compared by [`is_same_vol`](crate::is_same_vol).
+    ///
+    /// On Linux all subvolumes of a btrfs filesystem share the device number reported in
+    /// `/proc/self/mountinfo`, so they are the same filesystem.
+    Filesystem,
+    /// The same block device, as reported by
+    /// [`VolumeBackend::block_device`](crate::VolumeBackend::block_device).
+    ///
+    /// Filesystems without a backing block device (e.g. `tmpfs` or network filesystems)
+    /// are their own block device.
+    BlockDevice,
+    /// At least one common physical disk, as reported by
+    /// [`VolumeBackend::physical_disks`](crate::VolumeBackend::physical_disks).
+    ///
+    /// Volumes spanning several disks (RAID, LVM, dynamic disks) are on the same physical
+    /// disk as any volume sharing one of those disks.
+    PhysicalDisk,
+}
+
+/// Resolves paths to volume device paths using its own backend and mapping table.
+///
+/// The free functions of this crate ([`resolve_device_path`](crate::resolve_device_path),
+/// [`is_same_vol`](crate::is_same_vol), ...) delegate to a process-wide default instance.
+/// Creating separate instances lets components use different backends or refresh
+/// policies without affecting each other.
+///
+/// # Example
+/// ```rust
+/// use samevol::{FakeBackend, RefreshPolicy, Resolver};
+///
+/// let resolver = Resolver::builder()
+///     .backend(FakeBackend::unix().with_volume("8:1", ["/"]).with_volume("8:17", ["/home"]))
+///     .refresh_policy(RefreshPolicy::OnMiss)
+///     .build();
+///
+/// assert_eq!(resolver.resolve_device_path("/home/user").as_deref(), Some("8:17"));
+/// assert!(!resolver.is_same_vol("/etc", "/home/user"));
+/// ```
+pub struct Resolver {
+    backend: Arc<dyn VolumeBackend>,
+    refresh_policy: RefreshPolicy,
+    matcher: PathMatcher,
+    /// 当前映射表快照，`None` 表示尚未成功构建；读取方无锁访问
+    snapshot: ArcSwapOption<Snapshot>,
+    /// 串行化重建过程，仅由写入方持有
+    refresh: Mutex<RefreshState>,
+    /// `refresh_due` 的时间基准
+    epoch: Instant,
+    /// 定期刷新策略下，下次刷新的时间（自 `epoch` 起的纳秒数）
+    refresh_due: AtomicU64,
+}
+
+/// Initialization status of the volume mapping table of a [`Resolver`].
+///
+/// Returned by [`Resolver::init_status`] and [`init_status`](crate::init_status).
+#[derive(Debug, Clone)]
+pub enum InitStatus {
+    /// The table has not been built yet. It will be built on first use.
+    Uninitialized,
+    /// The table has been built successfully.
+    Initialized {
+        /// Number of volume mappings in the table.
+        mappings: usize,
+    },
+    /// The last attempt to build the table failed. It will be retried on the next call.
+    Failed(Error),
+}
+
+impl InitStatus {
+    /// Returns `true` if the table has been built successfully.
+    pub fn is_initialized(&self) -> bool {
+        matches!(self, InitStatus::Initialized { .. })
+    }
+}
+
+/// 不可变的卷映射表快照，构建完成后通过原子指针替换发布
+struct Snapshot {
+    /// 按后端枚举顺序排列的卷及其挂载点
+    volumes: Vec<VolumeInfo>,
+    /// 卷标识 -> `volumes` 中的下标
+    by_id: HashMap<VolumeId, usize>,
+    /// 按路径组件组织的挂载点索引，用于最长前缀匹配
+    index: MountIndex<Mount>,
+}
+
+/// 映射表中的一个挂载：挂载点及其所属的卷
+struct Mount {
+    volume: VolumeId,
+    mount_point: MountPoint,
+}
+
+/// A snapshot of all volumes known to a [`Resolver`] and their mount points.
+///
+/// Returned by [`Resolver::volumes`] and [`volumes`](crate::volumes). The snapshot is
+/// cheap to clone and does not change when the resolver rebuilds its table afterwards;
+/// call [`Resolver::volumes`] again to observe the new table.
+///
+/// # Example
+/// ```rust
+/// use samevol::{FakeBackend, Resolver};
+///
+/// let resolver = Resolver::builder()
+///     .backend(FakeBackend::windows().with_volume("C", [r"C:\"]).with_volume("D", [r"D:\", r"C:\Data"]))
+///     .build();
+///
+/// let volumes = resolver.volumes().unwrap();
+/// for volume in &volumes {
+///     println!("{}: {:?}", volume.id(), volume.mount_points());
+/// }
+/// assert_eq!(volumes.mount_points("D").unwrap().len(), 2);
+/// ```
+#[derive(Clone)]
+pub struct Volumes {
+    snapshot: Arc<Snapshot>,
+}
+
+impl Volumes {
+    /// Returns the number of volumes.
+    pub fn len(&self) -> usize {
+        self.snapshot.volumes.len()
+    }
+
+    /// Returns `true` if no volume is known.
+    pub fn is_empty(&self) -> bool {
+        self.snapshot.volumes.is_empty()
+    }
+
+    /// Returns an iterator over the volumes, in the order enumerated by the backend.
+    pub fn iter(&self) -> std::slice::Iter<'_, VolumeInfo> {
+        self.snapshot.volumes.iter()
+    }
+
+    /// Returns the volume with the given device path.
+    ///
+    /// The device path is brought into its canonical form first (see [`DevicePath`]), so
+    /// e.g. volume GUID paths match regardless of case and trailing backslash.
+    pub fn get(&self, device_path: &str) -> Option<&VolumeInfo> {
+        let index = match self.snapshot.by_id.get(device_path) {
+            Some(index) => *index,
+            None => *self.snapshot.by_id.get(DevicePath::parse(device_path).ok()?.as_str())?,
+        };
+        Some(&self.snapshot.volumes[index])
+    }
+
+    /// Returns the mount points of the volume with the given device path, or `None` if
+    /// the volume is unknown.
+    pub fn mount_points(&self, device_path: &str) -> Option<&[MountPoint]> {
+        self.get(device_path).map(VolumeInfo::mount_points)
+    }
+
+    /// Returns the path matcher mount points are compared with.
+    pub fn path_matcher(&self) -> PathMatcher {
+        self.snapshot.index.matcher()
+    }
+
+    /// 由卷列表构建快照（卷标识须唯一）
+    #[cfg_attr(not(feature = "serde"), allow(dead_code))]
+    pub(crate) fn from_volumes(volumes: Vec<VolumeInfo>, matcher: PathMatcher) -> Self {
+        Volumes { snapshot: Arc::new(Snapshot::new(volumes, &matcher)) }
+    }
+}
+
+impl fmt::Debug for Volumes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a> IntoIterator for &'a Volumes {
+    type Item = &'a VolumeInfo;
+    type IntoIter = std::slice::Iter<'a, VolumeInfo>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// 重建状态，只在持有重建锁时访问
+#[derive(Default)]
+struct RefreshState {
+    /// 尚未成功构建前，最近一次构建失败的原因
+    init_error: Option<Error>,
+}
+
+/// Builder for [`Resolver`].
+///
+/// Created with [`Resolver::builder`].
+pub struct ResolverBuilder {
+    backend: Option<Arc<dyn VolumeBackend>>,
+    refresh_policy: RefreshPolicy,
+    matcher: Option<PathMatcher>,
+}
+
+impl ResolverBuilder {
+    /// Sets the volume backend. Defaults to [`SystemBackend`].
+    pub fn backend<B: VolumeBackend + 'static>(mut self, backend: B) -> Self {
+        self.backend = Some(Arc::new(backend));
+        self
+    }
+
+    /// Sets the refresh policy. Defaults to [`RefreshPolicy::Manual`].
+    pub fn refresh_policy(mut self, refresh_policy: RefreshPolicy) -> Self {
+        self.refresh_policy = refresh_policy;
+        self
+    }
+
+    /// Sets how paths are matched against mount points. Defaults to the
+    /// [`path_matcher`](VolumeBackend::path_matcher) of the backend.
+    ///
+    /// Useful when the backend reports mount points in a different form than the
+    /// paths passed in, e.g. with different case.
+    pub fn path_matcher(mut self, matcher: PathMatcher) -> Self {
+        self.matcher = Some(matcher);
+        self
+    }
+
+    /// Creates the resolver.
+    ///
+    /// The volume mapping table is built lazily, on first use, or explicitly with
+    /// [`Resolver::try_init`].
+    pub fn build(self) -> Resolver {
+        let backend = self
+            .backend
+            .unwrap_or_else(|| Arc::new(SystemBackend::default()));
+        Resolver {
+            matcher: self.matcher.unwrap_or_else(|| backend.path_matcher()),
+            backend,
+            refresh_policy: self.refresh_policy,
+            snapshot: ArcSwapOption::empty(),
+            refresh: Mutex::new(RefreshState::default()),
+            epoch: Instant::now(),
+            refresh_due: AtomicU64::new(0),
+        }
+    }
+}
+
+impl Default for Resolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Resolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Resolver")
+            .field("refresh_policy", &self.refresh_policy)
+            .field("matcher", &self.matcher)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Resolver {
+    /// Creates a resolver using the [`SystemBackend`] and [`RefreshPolicy::Manual`].
+    pub fn new() -> Self {
+        Self::builder().build()
+    }
+
+    /// Returns a builder to configure a new resolver.
+    pub fn builder() -> ResolverBuilder {
+        ResolverBuilder {
+            backend: None,
+            refresh_policy: RefreshPolicy::default(),
+            matcher: None,
+        }
+    }
+
+    /// Returns the refresh policy of this resolver.
+    pub fn refresh_policy(&self) -> RefreshPolicy {
+        self.refresh_policy
+    }
+
+    /// Returns how this resolver matches paths against mount points.
+    pub fn path_matcher(&self) -> PathMatcher {
+        self.matcher
+    }
+
+    /// Builds the volume mapping table if it has not been built successfully yet.
+    ///
+    /// Lookups build the table on demand, so calling this is optional. It allows
+    /// reporting initialization failures up front, instead of on the first lookup.
+    ///
+    /// # Returns
+    /// - `Ok(usize)`: Number of volume mappings in the table
+    /// - `Err(Error)`: [`Error::VolumeEnumeration`] if building the table failed (it
+    ///   will be retried on the next call)
+    pub fn try_init(&self) -> Result<usize> {
+        if let Some(snapshot) = &*self.snapshot.load() {
+            return Ok(snapshot.mappings());
+        }
+        Ok(self.initialize()?.mappings())
+    }
+
+    /// Builds the volume mapping table if it has not been built successfully yet, without
+    /// failing.
+    ///
+    /// Same as [`try_init`](Self::try_init), but a failure yields `0`. The error can be
+    /// retrieved with [`init_status`](Self::init_status), and building the table is
+    /// retried on the next call.
+    pub fn init(&self) -> usize {
+        self.try_init().unwrap_or(0)
+    }
+
+    /// Returns the initialization status of the volume mapping table.
+    pub fn init_status(&self) -> InitStatus {
+        if let Some(snapshot) = &*self.snapshot.load() {
+            return InitStatus::Initialized { mappings: snapshot.mappings() };
+        }
+        match &self.lock_refresh().init_error {
+            Some(error) => InitStatus::Failed(error.clone()),
+            None => InitStatus::Uninitialized,
+        }
+    }
+
+    /// Rebuilds the volume mapping table from the backend.
+    ///
+    /// Lookups running concurrently are not blocked: they keep using the previous table
+    /// until the new one is swapped in atomically.
+    ///
+    /// # Returns
+    /// - `Ok(usize)`: Number of volume mappings found
+    /// - `Err(Error)`: [`Error::VolumeEnumeration`] if the backend failed to enumerate the
+    ///   volumes, in which case the previous table is kept
+    pub fn reinitialize(&self) -> Result<usize> {
+        let mut state = self.lock_refresh();
+        Ok(self.rebuild(&mut state)?.mappings())
+    }
+
+    /// Returns a snapshot of all volumes and their mount points.
+    ///
+    /// The volume mapping table is built first if needed, and refreshed if due under
+    /// [`RefreshPolicy::Interval`].
+    ///
+    /// # Errors
+    /// [`Error::VolumeEnumeration`] if the table has not been built yet and building it
+    /// failed.
+    pub fn volumes(&self) -> Result<Volumes> {
+        Ok(Volumes { snapshot: self.current_snapshot()? })
+    }
+
+    /// Returns the mount points of the volume with the given device path.
+    ///
+    /// See [`mount_points`](crate::mount_points) for details.
+    pub fn mount_points(&self, device_path: &str) -> Option<Vec<MountPoint>> {
+        self.volumes().ok()?.mount_points(device_path).map(<[MountPoint]>::to_vec)
+    }
+
+    /// Queries the filesystem attributes and capacity of the volume containing a path.
+    ///
+    /// See [`volume_metadata`](crate::volume_metadata) for details.
+    pub fn volume_metadata<P: AsRef<Path>>(&self, path: P) -> Result<VolumeMetadata> {
+        let volume = self.volume_info(self.try_resolve_volume(path)?)?;
+        self.query_metadata(&volume)
+    }
+
+    /// Queries the filesystem attributes and capacity of a volume, e.g. one listed by
+    /// [`volumes`](Self::volumes).
+    ///
+    /// See [`query_metadata`](crate::query_metadata) for details.
+    pub fn query_metadata(&self, volume: &VolumeInfo) -> Result<VolumeMetadata> {
+        self.backend
+            .volume_metadata(volume)
+            .map_err(|source| Error::Metadata { volume: volume.id().clone(), source })
+    }
+
+    /// Resolves the device path of volume for a given file system path.
+    ///
+    /// See [`resolve_device_path`](crate::resolve_device_path) for details.
+    pub fn resolve_device_path<P: AsRef<Path>>(&self, path: P) -> Option<String> {
+        self.try_resolve_device_path(path).ok()
+    }
+
+    /// Resolves the device path of volume for a given file system path, reporting why
+    /// it could not be resolved.
+    ///
+    /// See [`try_resolve_device_path`](crate::try_resolve_device_path) for details.
+    pub fn try_resolve_device_path<P: AsRef<Path>>(&self, path: P) -> Result<String> {
+        self.try_resolve_volume(path).map(|id| id.as_str().to_owned())
+    }
+
+    /// Resolves the identity of the volume containing a given file system path.
+    ///
+    /// See [`resolve_volume`](crate::resolve_volume) for details.
+    pub fn resolve_volume<P: AsRef<Path>>(&self, path: P) -> Option<VolumeId> {
+        self.try_resolve_volume(path).ok()
+    }
+
+    /// Resolves the identity of the volume containing a given file system path, reporting
+    /// why it could not be resolved.
+    ///
+    /// See [`try_resolve_device_path`](crate::try_resolve_device_path) for the possible
+    /// errors.
+    pub fn try_resolve_volume<P: AsRef<Path>>(&self, path: P) -> Result<VolumeId> {
+        self.resolve_mount(path.as_ref(), |mount| mount.volume.clone())
+    }
+
+    /// Resolves the volumes of many paths at once.
+    ///
+    /// See [`resolve_many`](crate::resolve_many) for details.
+    pub fn resolve_many<I, P>(&self, paths: I) -> Vec<Result<VolumeId>>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let mut batch = Batch::new(self);
+        paths.into_iter().map(|path| batch.resolve_volume(path)).collect()
+    }
+
+    /// Starts a batch of lookups sharing one snapshot of the volume mapping table.
+    ///
+    /// Unlike [`resolve_many`](Self::resolve_many), the paths do not need to be known up
+    /// front, which suits streaming a large number of paths through the resolver. See
+    /// [`Batch`] for details.
+    pub fn batch(&self) -> Batch<'_> {
+        Batch::new(self)
+    }
+
+    /// Groups many paths by the volume they reside on.
+    ///
+    /// See [`group_by_volume`](crate::group_by_volume) for details.
+    pub fn group_by_volume<I, P>(&self, paths: I) -> HashMap<VolumeId, Vec<PathBuf>>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let mut batch = Batch::new(self);
+        let mut groups: HashMap<VolumeId, Vec<PathBuf>> = HashMap::new();
+        for path in paths {
+            let path = path.as_ref();
+            if let Ok(volume_id) = batch.resolve_volume(path) {
+                groups.entry(volume_id).or_default().push(path.to_path_buf());
+            }
+        }
+        groups
+    }
+
+    /// Resolves the volumes of many paths at once, in parallel.
+    ///
+    /// Behaves like [`resolve_many`](Self::resolve_many), but spreads the work over the
+    /// rayon thread pool. Each worker thread keeps its own cache of parent directories.
+    #[cfg(feature = "rayon")]
+    pub fn par_resolve_many<P: AsRef<Path> + Sync>(&self, paths: &[P]) -> Vec<Result<VolumeId>> {
+        use rayon::prelude::*;
+
+        paths
+            .par_iter()
+            .map_init(|| Batch::new(self), |batch, path| batch.resolve_volume(path))
+            .collect()
+    }
+
+    /// Groups many paths by the volume they reside on, in parallel.
+    ///
+    /// Behaves like [`group_by_volume`](Self::group_by_volume), but spreads the work over
+    /// the rayon thread pool. Paths keep their relative order within each group.
+    #[cfg(feature = "rayon")]
+    pub fn par_group_by_volume<P: AsRef<Path> + Sync>(&self, paths: &[P]) -> HashMap<VolumeId, Vec<PathBuf>> {
+        let mut groups: HashMap<VolumeId, Vec<PathBuf>> = HashMap::new();
+        for (path, result) in paths.iter().zip(self.par_resolve_many(paths)) {
+            if let Ok(volume_id) = result {
+                groups.entry(volume_id).or_default().push(path.as_ref().to_path_buf());
+            }
+        }
+        groups
+    }
+
+    /// Predicts whether `src` can be renamed to `dst` without a copy.
+    ///
+    /// See [`can_rename`](crate::can_rename) for details.
+    pub fn can_rename<P: AsRef<Path>, Q: AsRef<Path>>(&self, src: P, dst: Q) -> bool {
+        self.try_can_rename(src, dst).unwrap_or(false)
+    }
+
+    /// Predicts whether `src` can be renamed to `dst` without a copy, reporting why it
+    /// could not be determined.
+    ///
+    /// See [`try_can_rename`](crate::try_can_rename) for details.
+    pub fn try_can_rename<P: AsRef<Path>, Q: AsRef<Path>>(&self, src: P, dst: Q) -> Result<bool> {
+        self.try_compare(src, dst, Granularity::Mount)
+    }
+
+    /// Checks if two paths are related at the given granularity.
+    ///
+    /// See [`compare`](crate::compare) for details.
+    pub fn compare<P: AsRef<Path>, Q: AsRef<Path>>(&self, path1: P, path2: Q, granularity: Granularity) -> bool {
+        self.try_compare(path1, path2, granularity).unwrap_or(false)
+    }
+
+    /// Checks if two paths are related at the given granularity, reporting why it could
+    /// not be determined.
+    ///
+    /// See [`try_compare`](crate::try_compare) for details.
+    pub fn try_compare<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        path1: P,
+        path2: Q,
+        granularity: Granularity,
+    ) -> Result<bool> {
+        let identity = |mount: &Mount| (mount.volume.clone(), mount.mount_point.clone());
+        let (volume1, mount1) = self.resolve_mount(path1.as_ref(), identity)?;
+        let (volume2, mount2) = self.resolve_mount(path2.as_ref(), identity)?;
+
+        match granularity {
+            // 优先比较实时查询的挂载 ID，映射表中的挂载点可能已过时
+            Granularity::Mount => match (self.mount_id(path1.as_ref())?, self.mount_id(path2.as_ref())?) {
+                (Some(id1), Some(id2)) => Ok(id1 == id2),
+                // 同一卷的不同挂载点之间能否重命名取决于平台
+                _ => Ok(volume1 == volume2 && (self.backend.renames_across_mount_points() || mount1 == mount2)),
+            },
+            // 更粗的粒度下，同一卷总是相同的
+            _ if volume1 == volume2 => Ok(true),
+            Granularity::Filesystem => Ok(false),
+            Granularity::BlockDevice => {
+                let device1 = self.query_backend(volume1, |backend, volume| backend.block_device(volume))?;
+                let device2 = self.query_backend(volume2, |backend, volume| backend.block_device(volume))?;
+                Ok(device1 == device2)
+            }
+            Granularity::PhysicalDisk => {
+                let disks1 = self.query_backend(volume1, |backend, volume| backend.physical_disks(volume))?;
+                let disks2 = self.query_backend(volume2, |backend, volume| backend.physical_disks(volume))?;
+                Ok(disks1.iter().any(|disk| disks2.contains(disk)))
+            }
+        }
+    }
+
+    /// Checks if two paths reside on the same volume.
+    ///
+    /// See [`is_same_vol`](crate::is_same_vol) for details.
+    pub fn is_same_vol<P: AsRef<Path>, Q: AsRef<Path>>(&self, path1: P, path2: Q) -> bool {
+        self.try_is_same_vol(path1, path2).unwrap_or(false)
+    }
+
+    /// Checks if two paths reside on the same volume, reporting why it could not be
+    /// determined.
+    ///
+    /// See [`try_is_same_vol`](crate::try_is_same_vol) for details.
+    pub fn try_is_same_vol<P: AsRef<Path>, Q: AsRef<Path>>(&self, path1: P, path2: Q) -> Result<bool> {
+        // 比较两个路径所在卷的标识
+        let vol1 = self.try_resolve_volume(path1)?;
+        let vol2 = self.try_resolve_volume(path2)?;
+
+        Ok(vol1 == vol2)
+    }
+
+    /// 向后端实时查询路径所在挂载的 ID，后端不支持时返回 `None`
+    fn mount_id(&self, path: &Path) -> Result<Option<u64>> {
+        let full_path = self.backend.full_path(path).map_err(|e| Error::from_path_error(path, e))?;
+        self.backend.mount_id(&full_path).map_err(|e| Error::from_path_error(path, e))
+    }
+
+    /// 获取卷的完整信息；解析与查找之间映射表可能被重建，此时以无挂载点的卷代替
+    fn volume_info(&self, volume_id: VolumeId) -> Result<VolumeInfo> {
+        Ok(match self.volumes()?.get(volume_id.as_str()) {
+            Some(volume) => volume.clone(),
+            None => VolumeInfo::new(volume_id, Vec::new()),
+        })
+    }
+
+    /// 向后端查询卷的附加信息，错误归类为 [`Error::Metadata`]
+    fn query_backend<T>(
+        &self,
+        volume_id: VolumeId,
+        query: impl FnOnce(&dyn VolumeBackend, &VolumeInfo) -> io::Result<T>,
+    ) -> Result<T> {
+        let volume = self.volume_info(volume_id)?;
+        query(&*self.backend, &volume).map_err(|source| Error::Metadata { volume: volume.id().clone(), source })
+    }
+
+    /// 解析路径所在挂载的挂载点
+    pub(crate) fn try_resolve_mount_point(&self, path: &Path) -> Result<MountPoint> {
+        self.resolve_mount(path, |mount| mount.mount_point.clone())
+    }
+
+    /// 查找路径所在的挂载，并从中取出所需的信息
+    fn resolve_mount<T>(&self, path: &Path, extract: impl Fn(&Mount) -> T) -> Result<T> {
+        // 获取挂载点路径
+        let mount_point = get_volume_mount_point(&*self.backend, path)
+            .map_err(|e| Error::from_path_error(path, e))?;
+
+        let no_mount_point = || Error::NoMountPoint { path: path.to_path_buf(), mount_point: mount_point.clone() };
+
+        // 快速路径：无锁读取当前快照
+        if !self.is_refresh_due() && let Some(snapshot) = &*self.snapshot.load() {
+            match snapshot.lookup(&mount_point) {
+                Some(mount) => return Ok(extract(mount)),
+                None if self.refresh_policy != RefreshPolicy::OnMiss => return Err(no_mount_point()),
+                None => {}
+            }
+        }
+
+        let snapshot = self.current_snapshot()?;
+        if let Some(mount) = snapshot.lookup(&mount_point) {
+            return Ok(extract(mount));
+        }
+
+        // 未命中时按策略刷新后重试一次
+        if self.refresh_policy == RefreshPolicy::OnMiss {
+            let snapshot = self.refresh_on_miss(&snapshot)?;
+            if let Some(mount) = snapshot.lookup(&mount_point) {
+                return Ok(extract(mount));
+            }
+        }
+
+        Err(no_mount_point())
+    }
+
+    /// 获取重建锁；快照总是原子替换，锁中毒时内部状态仍然一致，可以继续使用
+    fn lock_refresh(&self) -> MutexGuard<'_, RefreshState> {
+        self.refresh.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// 获取当前快照：尚未构建时构建，到达定期刷新时间时刷新
+    fn current_snapshot(&self) -> Result<Arc<Snapshot>> {
+        match self.snapshot.load_full() {
+            // 尚未成功构建（包括此前构建失败）时，每次调用都重新尝试并返回错误
+            None => self.initialize(),
+            Some(snapshot) if self.is_refresh_due() => Ok(self.refresh_on_interval(snapshot)),
+            Some(snapshot) => Ok(snapshot),
+        }
+    }
+
+    /// 首次构建映射表；等待锁期间其他线程已构建成功时直接使用其结果
+    fn initialize(&self) -> Result<Arc<Snapshot>> {
+        let mut state = self.lock_refresh();
+        match self.snapshot.load_full() {
+            Some(snapshot) => Ok(snapshot),
+            None => self.rebuild(&mut state),
+        }
+    }
+
+    /// 定期刷新：已有其他线程在刷新时直接使用当前快照，刷新失败时继续使用原有映射表
+    fn refresh_on_interval(&self, current: Arc<Snapshot>) -> Arc<Snapshot> {
+        let mut state = match self.refresh.try_lock() {
+            Ok(state) => state,
+            Err(TryLockError::Poisoned(e)) => e.into_inner(),
+            Err(TryLockError::WouldBlock) => return current,
+        };
+        if !self.is_refresh_due() {
+            return self.snapshot.load_full().unwrap_or(current);
+        }
+        self.rebuild(&mut state).unwrap_or(current)
+    }
+
+    /// 未命中时刷新；等待锁期间其他线程已发布新快照时直接使用新快照
+    fn refresh_on_miss(&self, seen: &Arc<Snapshot>) -> Result<Arc<Snapshot>> {
+        let mut state = self.lock_refresh();
+        match self.snapshot.load_full() {
+            Some(snapshot) if !Arc::ptr_eq(&snapshot, seen) => Ok(snapshot),
+            _ => self.rebuild(&mut state),
+        }
+    }
+
+    /// 在持有重建锁的情况下重建并发布快照，失败时保留原有快照
+    fn rebuild(&self, state: &mut RefreshState) -> Result<Arc<Snapshot>> {
+        self.schedule_refresh();
+
+        match build_volume_map(&*self.backend) {
+            Ok(volumes) => {
+                let snapshot = Arc::new(Snapshot::new(volumes, &self.matcher));
+                self.snapshot.store(Some(snapshot.clone()));
+                state.init_error = None;
+                Ok(snapshot)
+            }
+            Err(source) => {
+                // 尚未初始化时保留错误供状态查询
+                let error = Error::VolumeEnumeration { source };
+                if self.snapshot.load().is_none() {
+                    state.init_error = Some(error.clone());
+                }
+                Err(error)
+            }
+        }
+    }
+
+    /// 自 `epoch` 起经过的纳秒数
+    fn elapsed_nanos(&self) -> u64 {
+        u64::try_from(self.epoch.elapsed().as_nanos()).unwrap_or(u64::MAX)
+    }
+
+    /// 定期刷新策略下是否已到刷新时间
+    fn is_refresh_due(&self) -> bool {
+        matches!(self.refresh_policy, RefreshPolicy::Interval(_))
+            && self.elapsed_nanos() >= self.refresh_due.load(Ordering::Relaxed)
+    }
+
+    /// 记录下次定期刷新的时间（无论本次构建成功与否）
+    fn schedule_refresh(&self) {
+        if let RefreshPolicy::Interval(interval) = self.refresh_policy {
+            let interval = u64::try_from(interval.as_nanos()).unwrap_or(u64::MAX);
+            self.refresh_due.store(self.elapsed_nanos().saturating_add(interval), Ordering::Relaxed);
+        }
+    }
+}
+
+impl Snapshot {
+    fn new(volumes: Vec<VolumeInfo>, matcher: &PathMatcher) -> Self {
+        let mut by_id = HashMap::with_capacity(volumes.len());
+        let mut index = MountIndex::new(*matcher);
+        for (i, volume) in volumes.iter().enumerate() {
+            by_id.insert(volume.id().clone(), i);
+            for mount_point in volume.mount_points() {
+                index.insert(mount_point, Mount { volume: volume.id().clone(), mount_point: mount_point.clone() });
+            }
+        }
+        Snapshot { volumes, by_id, index }
+    }
+
+    /// 映射表中挂载点的数量
+    fn mappings(&self) -> usize {
+        self.index.len()
+    }
+
+    /// 查找最长匹配的挂载点
+    fn lookup(&self, mount_point: &OsStr) -> Option<&Mount> {
+        self.index.longest_match(mount_point)
+    }
+}
+
+/// A batch of lookups sharing one snapshot of the volume mapping table.
+///
+/// Created with [`Resolver::batch`]. All lookups of a batch see the same table, even if
+/// the resolver rebuilds it meanwhile, and mount point queries are cached per parent
+/// directory, so that resolving many files of the same directory is cheap. Under
+/// [`RefreshPolicy::OnMiss`], the batch refreshes the table at most once.
+///
+/// # Example
+/// ```rust
+/// use samevol::{FakeBackend, Resolver};
+///
+/// let resolver = Resolver::builder()
+///     .backend(FakeBackend::unix().with_volume("8:1", ["/"]).with_volume("8:17", ["/home"]))
+///     .build();
+///
+/// let mut batch = resolver.batch();
+/// for path in ["/etc/hosts", "/home/user/notes.txt"] {
+///     let resolution = batch.resolve(path).unwrap();
+///     println!("{}: {} at {}", path, resolution.volume(), resolution.mount_point());
+/// }
+/// ```
+pub struct Batch<'a> {
+    resolver: &'a Resolver,
+    /// 快照或获取快照失败的原因（每个路径都返回该错误）
+    snapshot: Result<Arc<Snapshot>>,
+    /// 是否已因未命中而刷新过（每批最多刷新一次）
+    refreshed: bool,
+    /// 父目录完整路径 -> 挂载点
+    parents: HashMap<PathBuf, OsString>,
+}
+
+impl fmt::Debug for Batch<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Batch")
+            .field("mappings", &self.snapshot.as_ref().map(|snapshot| snapshot.mappings()))
+            .field("refreshed", &self.refreshed)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a> Batch<'a> {
+    fn new(resolver: &'a Resolver) -> Self {
+        Batch { resolver, snapshot: resolver.current_snapshot(), refreshed: false, parents: HashMap::new() }
+    }
+
+    /// Resolves the volume of a path and the mount point it is reached through.
+    ///
+    /// # Errors
+    /// See [`try_resolve_device_path`](crate::try_resolve_device_path). If building the
+    /// volume mapping table failed when the batch was created, every lookup returns that
+    /// error.
+    pub fn resolve<P: AsRef<Path>>(&mut self, path: P) -> Result<Resolution> {
+        self.lookup(path.as_ref(), |mount| Resolution::new(mount.volume.clone(), mount.mount_point.clone()))
+    }
+
+    /// Resolves the volume of a path.
+    ///
+    /// Same as [`resolve`](Self::resolve), without the mount point.
+    pub fn resolve_volume<P: AsRef<Path>>(&mut self, path: P) -> Result<VolumeId> {
+        self.lookup(path.as_ref(), |mount| mount.volume.clone())
+    }
+
+    /// 在批次快照中查找路径所在的挂载，并从中取出所需的信息
+    fn lookup<T>(&mut self, path: &Path, extract: impl Fn(&Mount) -> T) -> Result<T> {
+        let snapshot = self.snapshot.clone()?;
+        let backend = &*self.resolver.backend;
+        let full_path = backend.full_path(path).map_err(|e| Error::from_path_error(path, e))?;
+        let mount_point = self.mount_point(&snapshot, &full_path).map_err(|e| Error::from_path_error(path, e))?;
+
+        if let Some(mount) = snapshot.lookup(&mount_point) {
+            return Ok(extract(mount));
+        }
+
+        // 未命中时按策略刷新后重试一次
+        if self.resolver.refresh_policy == RefreshPolicy::OnMiss && !self.refreshed {
+            self.refreshed = true;
+            self.snapshot = self.resolver.refresh_on_miss(&snapshot);
+            self.parents.clear();
+            return self.lookup(path, extract);
+        }
+
+        Err(Error::NoMountPoint { path: path.to_path_buf(), mount_point })
+    }
+
+    /// 获取完整路径所在的挂载点
+    ///
+    /// 路径本身是已知挂载点或链接时直接查询；否则与父目录位于同一卷，父目录的查询结果可被同目录下的其他路径复用。
+    fn mount_point(&mut self, snapshot: &Snapshot, full_path: &Path) -> io::Result<OsString> {
+        let backend = &*self.resolver.backend;
+        // 链接（符号链接、目录联接等）可能指向其他卷，不能沿用父目录的结果
+        if snapshot.index.get(full_path).is_some() || backend.is_link(full_path) {
+            return backend.volume_mount_point(full_path);
+        }
+
+        let Some(parent) = full_path.parent().filter(|parent| !parent.as_os_str().is_empty()) else {
+            return backend.volume_mount_point(full_path);
+        };
+        if let Some(mount_point) = self.parents.get(parent) {
+            return Ok(mount_point.clone());
+        }
+
+        // 父目录查询失败时退回到直接查询路径本身
+        match backend.volume_mount_point(parent) {
+            Ok(mount_point) => {
+                self.parents.insert(parent.to_path_buf(), mount_point.clone());
+                Ok(mount_point)
+            }
+            Err(_) => backend.volume_mount_point(full_path),
+        }
+    }
+}
+
+/// 从后端枚举卷及其挂载点，同一卷被多次报告时合并其挂载点
+fn build_volume_map(backend: &dyn VolumeBackend) -> io::Result<Vec<VolumeInfo>> {
+    let mut volumes: Vec<(VolumeId, Vec<MountPoint>)> = Vec::new();
+    let mut positions: HashMap<VolumeId, usize> = HashMap::new();
+
+    for (device_path, mount_points) in backend.volumes()? {
+        let volume_id = DevicePath::parse(&device_path)
+            .map(VolumeId::from)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        let index = *positions.entry(volume_id.clone()).or_insert_with(|| {
+            volumes.push((volume_id, Vec::new()));
+            volumes.len() - 1
+        });
+        let entry = &mut volumes[index].1;
+        for mount_point in mount_points {
+            let mount_point = MountPoint::new(mount_point);
+            if !entry.contains(&mount_point) {
+                entry.push(mount_point);
+            }
+        }
+    }
+
+    Ok(volumes.into_iter().map(|(id, mount_points)| VolumeInfo::new(id, mount_points)).collect())
+}
+
+/// 获取给定路径所在的卷挂载点
+fn get_volume_mount_point(backend: &dyn VolumeBackend, path: &Path) -> io::Result<OsString> {
+    // 第一步：获取绝对路径
+    let full_path = backend.full_path(path)?;
+    // 第二步：获取挂载点路径
+    backend.volume_mount_point(&full_path)
+}
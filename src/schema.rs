@@ -0,0 +1,286 @@
+/*
+ * Copyright 2025 爱佐 (Ayrzo)
+ *
+ * This file is part of cargo crate samevol (https://crates.io/crates/samevol),
+ * which licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! serde 支持：结果类型的序列化，以及卷映射表快照的版本化 JSON 格式
+//!
+//! 标识与挂载点序列化为字符串，反序列化时与解析时一样规范化。
+
+use std::ffi::OsString;
+use std::io;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+use crate::matching::PathMatcher;
+use crate::{DevicePath, MountPoint, VolumeId, VolumeInfo, VolumeMetadata, Volumes};
+
+impl Serialize for DevicePath {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for DevicePath {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        DevicePath::parse(&s).map_err(de::Error::custom)
+    }
+}
+
+impl Serialize for VolumeId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for VolumeId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        DevicePath::deserialize(deserializer).map(VolumeId::from)
+    }
+}
+
+/// 挂载点的序列化格式：通常为字符串，非 Unicode 的挂载点以平台原生编码无损表示
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum MountPointRepr {
+    Str(String),
+    /// Unix 上的原始字节
+    Bytes { bytes: Vec<u8> },
+    /// Windows 上的 UTF-16 编码单元（可能包含未配对的代理项）
+    Wide { wide: Vec<u16> },
+}
+
+impl Serialize for MountPoint {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let path = self.as_os_str();
+        let repr = match path.to_str() {
+            Some(s) => MountPointRepr::Str(s.to_owned()),
+            #[cfg(unix)]
+            None => MountPointRepr::Bytes { bytes: std::os::unix::ffi::OsStrExt::as_bytes(path).to_vec() },
+            #[cfg(windows)]
+            None => MountPointRepr::Wide { wide: std::os::windows::ffi::OsStrExt::encode_wide(path).collect() },
+            #[cfg(not(any(unix, windows)))]
+            None => return Err(serde::ser::Error::custom("mount point is not valid Unicode")),
+        };
+        repr.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for MountPoint {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let path: OsString = match MountPointRepr::deserialize(deserializer)? {
+            MountPointRepr::Str(s) => s.into(),
+            #[cfg(unix)]
+            MountPointRepr::Bytes { bytes } => std::os::unix::ffi::OsStringExt::from_vec(bytes),
+            #[cfg(windows)]
+            MountPointRepr::Wide { wide } => std::os::windows::ffi::OsStringExt::from_wide(&wide),
+            // 其他平台的原生编码只能在可转换为 Unicode 时使用
+            #[cfg(not(unix))]
+            MountPointRepr::Bytes { bytes } => String::from_utf8(bytes)
+                .map_err(|_| de::Error::custom("mount point is not valid Unicode on this platform"))?
+                .into(),
+            #[cfg(not(windows))]
+            MountPointRepr::Wide { wide } => String::from_utf16(&wide)
+                .map_err(|_| de::Error::custom("mount point is not valid Unicode on this platform"))?
+                .into(),
+        };
+        Ok(MountPoint::new(path))
+    }
+}
+
+/// 卷元数据的序列化格式：容量展开为与访问方法同名的字段
+#[derive(Serialize, Deserialize)]
+struct MetadataRepr {
+    fs_type: Option<String>,
+    label: Option<String>,
+    serial_number: Option<u32>,
+    total_bytes: Option<u64>,
+    free_bytes: Option<u64>,
+    available_bytes: Option<u64>,
+}
+
+impl Serialize for VolumeMetadata {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        MetadataRepr {
+            fs_type: self.fs_type().map(str::to_owned),
+            label: self.label().map(str::to_owned),
+            serial_number: self.serial_number(),
+            total_bytes: self.total_bytes(),
+            free_bytes: self.free_bytes(),
+            available_bytes: self.available_bytes(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for VolumeMetadata {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = MetadataRepr::deserialize(deserializer)?;
+        let mut metadata = VolumeMetadata::new();
+        if let Some(fs_type) = repr.fs_type {
+            metadata = metadata.with_fs_type(fs_type);
+        }
+        if let Some(label) = repr.label {
+            metadata = metadata.with_label(label);
+        }
+        if let Some(serial_number) = repr.serial_number {
+            metadata = metadata.with_serial_number(serial_number);
+        }
+        // 容量的三个字段总是同时存在
+        match (repr.total_bytes, repr.free_bytes, repr.available_bytes) {
+            (Some(total), Some(free), Some(available)) => metadata = metadata.with_capacity(total, free, available),
+            (None, None, None) => {}
+            _ => return Err(de::Error::custom("capacity fields must be given together")),
+        }
+        Ok(metadata)
+    }
+}
+
+/// 快照的序列化格式（借用）
+#[derive(Serialize)]
+struct VolumesRef<'a> {
+    version: u32,
+    path_matcher: PathMatcher,
+    volumes: &'a [VolumeInfo],
+}
+
+/// 快照的序列化格式（反序列化）
+#[derive(Deserialize)]
+struct VolumesRepr {
+    version: u32,
+    path_matcher: PathMatcher,
+    volumes: Vec<VolumeInfo>,
+}
+
+impl Serialize for Volumes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        VolumesRef { version: Volumes::SCHEMA_VERSION, path_matcher: self.path_matcher(), volumes: self.iter().as_slice() }
+            .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Volumes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = VolumesRepr::deserialize(deserializer)?;
+        if repr.version != Volumes::SCHEMA_VERSION {
+            return Err(de::Error::custom(format!("unsupported schema version {}", repr.version)));
+        }
+        for (i, volume) in repr.volumes.iter().enumerate() {
+            if repr.volumes[..i].iter().any(|other| other.id() == volume.id()) {
+                return Err(de::Error::custom(format!("duplicate volume {}", volume.id())));
+            }
+        }
+        Ok(Volumes::from_volumes(repr.volumes, repr.path_matcher))
+    }
+}
+
+impl Volumes {
+    /// Version of the serialization format of [`Volumes`].
+    ///
+    /// Serialized snapshots carry this number in their `version` field, and
+    /// deserializing a snapshot of another version fails. It is incremented whenever the
+    /// format changes incompatibly. Version 1 looks like this:
+    ///
+    /// ```json
+    /// {
+    ///   "version": 1,
+    ///   "path_matcher": { "syntax": "windows", "case_folding": "ntfs" },
+    ///   "volumes": [
+    ///     { "id": "\\\\?\\Volume{e8a7f3c2-0000-0000-0000-100000000000}\\", "mount_points": ["C:\\"] }
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Mount points are strings. Those that are not valid Unicode are written losslessly
+    /// in the encoding of the platform instead: `{ "bytes": [...] }` with the raw bytes
+    /// on Unix, `{ "wide": [...] }` with the UTF-16 code units on Windows.
+    pub const SCHEMA_VERSION: u32 = 1;
+
+    /// Serializes the snapshot to JSON, see [`SCHEMA_VERSION`](Self::SCHEMA_VERSION) for
+    /// the format.
+    ///
+    /// # Errors
+    /// Only fails if the serializer does, which does not happen when writing to a
+    /// string.
+    ///
+    /// # Example
+    /// ```rust
+    /// use samevol::{FakeBackend, Resolver, Volumes};
+    ///
+    /// let resolver = Resolver::builder()
+    ///     .backend(FakeBackend::unix().with_volume("8:1", ["/"]).with_volume("8:17", ["/home"]))
+    ///     .build();
+    ///
+    /// let json = resolver.volumes().unwrap().to_json().unwrap();
+    /// let volumes = Volumes::from_json(&json).unwrap();
+    /// assert_eq!(volumes.mount_points("8:17").unwrap()[0].as_os_str(), "/home/");
+    /// ```
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Deserializes a snapshot from JSON, as written by [`to_json`](Self::to_json).
+    ///
+    /// Device paths and mount points are brought into their canonical form, as when
+    /// enumerated from a backend. To resolve paths against the loaded snapshot, build a
+    /// [`Resolver`](crate::Resolver) on an
+    /// [`OfflineBackend::from_volumes`](crate::OfflineBackend::from_volumes).
+    ///
+    /// # Errors
+    /// Fails if the JSON is malformed, of another
+    /// [schema version](Self::SCHEMA_VERSION), contains an invalid device path or lists a
+    /// volume twice.
+    ///
+    /// # Example
+    /// ```rust
+    /// use samevol::{OfflineBackend, Resolver, Volumes};
+    ///
+    /// let json = r#"{
+    ///     "version": 1,
+    ///     "path_matcher": { "syntax": "unix", "case_folding": "exact" },
+    ///     "volumes": [
+    ///         { "id": "8:1", "mount_points": ["/"] },
+    ///         { "id": "8:17", "mount_points": ["/home"] }
+    ///     ]
+    /// }"#;
+    /// let volumes = Volumes::from_json(json).unwrap();
+    /// let resolver = Resolver::builder().backend(OfflineBackend::from_volumes(&volumes)).build();
+    /// assert_eq!(resolver.resolve_device_path("/home/user").as_deref(), Some("8:17"));
+    /// ```
+    pub fn from_json(json: &str) -> serde_json::Result<Volumes> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Exports the volume mapping table of the default resolver to JSON.
+///
+/// Shortcut for [`volumes`](crate::volumes) followed by [`Volumes::to_json`]. Load the
+/// JSON back with [`Volumes::from_json`], and resolve paths against it with an
+/// [`OfflineBackend::from_volumes`](crate::OfflineBackend::from_volumes).
+///
+/// # Errors
+/// The error of [`volumes`](crate::volumes), converted to an [`io::Error`].
+///
+/// # Example
+/// ```rust,no_run
+/// let json = samevol::export_volume_map()?;
+/// std::fs::write("volumes.json", json)?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn export_volume_map() -> io::Result<String> {
+    let volumes = crate::volumes()?;
+    volumes.to_json().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
@@ -0,0 +1,510 @@
+/*
+ * Copyright 2025 爱佐 (Ayrzo)
+ *
+ * This file is part of cargo crate samevol (https://crates.io/crates/samevol),
+ * which licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! 强类型的卷标识、设备路径和挂载点
+
+use std::borrow::Borrow;
+use std::error::Error as StdError;
+use std::ffi::{OsStr, OsString};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::guid::{Guid, VolumeGuidPath};
+
+/// Error returned when parsing a [`DevicePath`], [`VolumeId`] or [`MountPoint`] fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    what: &'static str,
+    input: String,
+    reason: &'static str,
+}
+
+impl ParseError {
+    pub(crate) fn new(what: &'static str, input: &str, reason: &'static str) -> Self {
+        ParseError { what, input: input.to_owned(), reason }
+    }
+
+    /// Returns the input that failed to parse.
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid {} `{}`: {}", self.what, self.input, self.reason)
+    }
+}
+
+impl StdError for ParseError {}
+
+/// The device path of a volume, as reported by the backend.
+///
+/// Known formats are validated and brought into a canonical form when parsing:
+/// - Windows volume GUID paths, formatted as `\\?\Volume{xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx}\`
+///   with a lowercase GUID and a trailing backslash
+/// - Linux device numbers, formatted as `major:minor` in decimal
+///
+/// Any other non-empty string (e.g. a device path reported by a custom backend) is kept
+/// verbatim.
+///
+/// # Example
+/// ```rust
+/// use samevol::DevicePath;
+///
+/// let path: DevicePath = r"\\?\volume{E8A7F3C2-0000-0000-0000-100000000000}".parse().unwrap();
+/// assert_eq!(path.to_string(), r"\\?\Volume{e8a7f3c2-0000-0000-0000-100000000000}\");
+/// assert!(path.is_volume_guid());
+///
+/// let path: DevicePath = "8:17".parse().unwrap();
+/// assert_eq!(path.device_number(), Some((8, 17)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DevicePath(String);
+
+impl DevicePath {
+    /// Parses a device path, see [`DevicePath`] for the accepted formats.
+    ///
+    /// # Errors
+    /// Returns a [`ParseError`] if the input is empty, contains NUL or whitespace
+    /// characters, or looks like a volume GUID path but is malformed.
+    pub fn parse(s: &str) -> Result<Self, ParseError> {
+        const WHAT: &str = "device path";
+
+        if s.is_empty() {
+            return Err(ParseError::new(WHAT, s, "empty"));
+        }
+        if s.chars().any(|c| c == '\0' || c.is_whitespace()) {
+            return Err(ParseError::new(WHAT, s, "contains NUL or whitespace"));
+        }
+
+        // 卷 GUID 路径：统一前缀大小写、GUID 小写并确保结尾反斜杠
+        if VolumeGuidPath::has_prefix(s) {
+            let path = VolumeGuidPath::parse(s)
+                .map_err(|_| ParseError::new(WHAT, s, "malformed volume GUID path"))?;
+            return Ok(DevicePath(path.to_string()));
+        }
+
+        // 设备号：统一为十进制格式
+        if let Some((major, minor)) = parse_device_number(s) {
+            return Ok(DevicePath(format!("{}:{}", major, minor)));
+        }
+
+        Ok(DevicePath(s.to_owned()))
+    }
+
+    /// Returns the device path as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns `true` if this is a Windows volume GUID path.
+    pub fn is_volume_guid(&self) -> bool {
+        VolumeGuidPath::has_prefix(&self.0)
+    }
+
+    /// Returns the volume GUID if this is a Windows volume GUID path.
+    pub fn volume_guid(&self) -> Option<Guid> {
+        VolumeGuidPath::parse(&self.0).ok().map(|path| path.guid())
+    }
+
+    /// Returns the `(major, minor)` device number if this is a Linux device number.
+    pub fn device_number(&self) -> Option<(u32, u32)> {
+        parse_device_number(&self.0)
+    }
+
+    /// Returns the identity of the volume this device path refers to.
+    pub fn volume_id(&self) -> VolumeId {
+        VolumeId(Arc::from(self.0.as_str()))
+    }
+}
+
+impl FromStr for DevicePath {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DevicePath::parse(s)
+    }
+}
+
+impl fmt::Display for DevicePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for DevicePath {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<DevicePath> for String {
+    fn from(path: DevicePath) -> Self {
+        path.0
+    }
+}
+
+/// 解析 `major:minor` 格式的设备号
+fn parse_device_number(s: &str) -> Option<(u32, u32)> {
+    let (major, minor) = s.split_once(':')?;
+    let digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    if !digits(major) || !digits(minor) {
+        return None;
+    }
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
+
+/// The identity of a volume.
+///
+/// Two paths are on the same volume if and only if their volume IDs are equal. A
+/// `VolumeId` is cheap to clone (it is reference counted), implements [`Eq`], [`Hash`]
+/// and [`Ord`], and can therefore be used directly as a map key, e.g. to group paths by
+/// volume. It can also be looked up by its string form, since it implements
+/// `Borrow<str>`.
+///
+/// # Example
+/// ```rust
+/// use std::collections::HashMap;
+/// use samevol::{FakeBackend, Resolver, VolumeId};
+///
+/// let resolver = Resolver::builder()
+///     .backend(FakeBackend::unix().with_volume("8:1", ["/"]).with_volume("8:17", ["/home"]))
+///     .build();
+///
+/// let mut groups: HashMap<VolumeId, Vec<&str>> = HashMap::new();
+/// for path in ["/etc/hosts", "/home/a", "/usr/bin", "/home/b"] {
+///     let id = resolver.resolve_volume(path).unwrap();
+///     groups.entry(id).or_default().push(path);
+/// }
+/// assert_eq!(groups["8:17"], ["/home/a", "/home/b"]);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct VolumeId(Arc<str>);
+
+impl VolumeId {
+    /// Returns the volume ID as a string slice. This is the canonical device path.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the device path of the volume.
+    pub fn device_path(&self) -> DevicePath {
+        DevicePath(self.0.to_string())
+    }
+}
+
+impl From<DevicePath> for VolumeId {
+    fn from(path: DevicePath) -> Self {
+        VolumeId(Arc::from(path.0))
+    }
+}
+
+impl FromStr for VolumeId {
+    type Err = ParseError;
+
+    /// Parses a device path and returns its volume ID.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DevicePath::parse(s).map(VolumeId::from)
+    }
+}
+
+impl fmt::Debug for VolumeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("VolumeId").field(&&*self.0).finish()
+    }
+}
+
+impl fmt::Display for VolumeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for VolumeId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for VolumeId {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+/// A mount point: the root directory of a volume in the file system namespace.
+///
+/// Mount points always end with a path separator, so that a plain prefix comparison
+/// respects component boundaries (`C:\foo\` is not a prefix of `C:\foobar\`). The
+/// separator is appended when missing: a backslash for Windows-style paths (containing a
+/// backslash or starting with a drive letter), a slash otherwise.
+///
+/// The underlying [`OsString`] is kept unchanged otherwise, so paths that are not valid
+/// Unicode round-trip losslessly.
+///
+/// # Example
+/// ```rust
+/// use samevol::MountPoint;
+///
+/// let mount_point: MountPoint = r"D:\Vdisks\Wechat".parse().unwrap();
+/// assert_eq!(mount_point.to_string(), r"D:\Vdisks\Wechat\");
+///
+/// let mount_point = MountPoint::new("/mnt/data");
+/// assert_eq!(mount_point.as_os_str(), "/mnt/data/");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MountPoint(OsString);
+
+impl MountPoint {
+    /// Creates a mount point, appending a trailing separator if missing.
+    pub fn new<S: Into<OsString>>(path: S) -> Self {
+        let mut path = path.into();
+        let bytes = path.as_encoded_bytes();
+        let separator = match bytes.last() {
+            Some(b'\\' | b'/') => None,
+            _ if bytes.contains(&b'\\') || (bytes.len() >= 2 && bytes[1] == b':') => Some("\\"),
+            _ => Some("/"),
+        };
+        if let Some(separator) = separator {
+            path.push(separator);
+        }
+        MountPoint(path)
+    }
+
+    /// Returns the mount point as an [`OsStr`].
+    pub fn as_os_str(&self) -> &OsStr {
+        &self.0
+    }
+
+    /// Returns the mount point as a [`Path`].
+    pub fn as_path(&self) -> &Path {
+        Path::new(&self.0)
+    }
+
+    /// Converts the mount point into an [`OsString`].
+    pub fn into_os_string(self) -> OsString {
+        self.0
+    }
+
+    /// Returns `true` if `path` is this mount point or lies below it, comparing the
+    /// encoded bytes of both.
+    pub fn contains<P: AsRef<Path>>(&self, path: P) -> bool {
+        let path = path.as_ref().as_os_str().as_encoded_bytes();
+        let mount_point = self.0.as_encoded_bytes();
+        path.starts_with(mount_point) || path == &mount_point[..mount_point.len() - 1]
+    }
+}
+
+impl FromStr for MountPoint {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseError::new("mount point", s, "empty"));
+        }
+        Ok(MountPoint::new(s))
+    }
+}
+
+impl fmt::Display for MountPoint {
+    /// Non-Unicode sequences are displayed lossily.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_path().display().fmt(f)
+    }
+}
+
+impl AsRef<OsStr> for MountPoint {
+    fn as_ref(&self) -> &OsStr {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for MountPoint {
+    fn as_ref(&self) -> &Path {
+        self.as_path()
+    }
+}
+
+impl From<MountPoint> for PathBuf {
+    fn from(mount_point: MountPoint) -> Self {
+        PathBuf::from(mount_point.0)
+    }
+}
+
+/// A volume together with all of its mount points.
+///
+/// Returned when enumerating the volume mapping table, see
+/// [`volumes`](crate::volumes). A volume may be mounted at several places (e.g. a drive
+/// letter and a folder mount point on Windows), or at none at all.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VolumeInfo {
+    id: VolumeId,
+    mount_points: Vec<MountPoint>,
+}
+
+impl VolumeInfo {
+    /// Creates the information of a volume from its identity and mount points.
+    pub fn new(id: VolumeId, mount_points: Vec<MountPoint>) -> Self {
+        VolumeInfo { id, mount_points }
+    }
+
+    /// Returns the identity of the volume.
+    pub fn id(&self) -> &VolumeId {
+        &self.id
+    }
+
+    /// Returns the mount points of the volume, in the order reported by the backend.
+    pub fn mount_points(&self) -> &[MountPoint] {
+        &self.mount_points
+    }
+}
+
+/// The volume a path resides on, together with the mount point it was reached through.
+///
+/// Returned by [`Batch::resolve`](crate::Batch::resolve).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Resolution {
+    volume: VolumeId,
+    mount_point: MountPoint,
+}
+
+impl Resolution {
+    /// Creates a resolution result from a volume and one of its mount points.
+    pub fn new(volume: VolumeId, mount_point: MountPoint) -> Self {
+        Resolution { volume, mount_point }
+    }
+
+    /// Returns the volume the path resides on.
+    pub fn volume(&self) -> &VolumeId {
+        &self.volume
+    }
+
+    /// Returns the mount point of the volume that contains the path.
+    ///
+    /// For a path below a nested mount, this is the innermost mount point.
+    pub fn mount_point(&self) -> &MountPoint {
+        &self.mount_point
+    }
+}
+
+/// Filesystem attributes and capacity of a volume.
+///
+/// Returned by [`volume_metadata`](crate::volume_metadata). Every attribute is optional,
+/// since not every platform or filesystem reports all of them: on Linux, for example,
+/// the label and serial number are not available from `mountinfo` and `statvfs`.
+///
+/// Backends build the metadata with the `with_*` methods, e.g. to fake it in tests:
+///
+/// # Example
+/// ```rust
+/// use samevol::VolumeMetadata;
+///
+/// let metadata = VolumeMetadata::new()
+///     .with_fs_type("NTFS")
+///     .with_label("Data")
+///     .with_capacity(500 << 30, 120 << 30, 100 << 30);
+/// assert_eq!(metadata.fs_type(), Some("NTFS"));
+/// assert_eq!(metadata.available_bytes(), Some(100 << 30));
+/// assert_eq!(metadata.serial_number(), None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VolumeMetadata {
+    fs_type: Option<String>,
+    label: Option<String>,
+    serial_number: Option<u32>,
+    capacity: Option<Capacity>,
+}
+
+/// 容量信息，三者总是同时获取
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Capacity {
+    total: u64,
+    free: u64,
+    available: u64,
+}
+
+impl VolumeMetadata {
+    /// Creates metadata with no attribute set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the filesystem type, e.g. `NTFS` or `ext4`.
+    pub fn with_fs_type<S: Into<String>>(mut self, fs_type: S) -> Self {
+        self.fs_type = Some(fs_type.into());
+        self
+    }
+
+    /// Sets the volume label.
+    pub fn with_label<S: Into<String>>(mut self, label: S) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Sets the volume serial number.
+    pub fn with_serial_number(mut self, serial_number: u32) -> Self {
+        self.serial_number = Some(serial_number);
+        self
+    }
+
+    /// Sets the capacity, in bytes.
+    ///
+    /// # Arguments
+    /// * `total` - Total size of the volume
+    /// * `free` - Free space on the volume
+    /// * `available` - Free space available to the current user, which may be less than
+    ///   `free` because of quotas or reserved blocks
+    pub fn with_capacity(mut self, total: u64, free: u64, available: u64) -> Self {
+        self.capacity = Some(Capacity { total, free, available });
+        self
+    }
+
+    /// Returns the filesystem type, e.g. `NTFS` or `ext4`.
+    pub fn fs_type(&self) -> Option<&str> {
+        self.fs_type.as_deref()
+    }
+
+    /// Returns the volume label. Empty labels are reported as `Some("")`.
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// Returns the volume serial number.
+    pub fn serial_number(&self) -> Option<u32> {
+        self.serial_number
+    }
+
+    /// Returns the total size of the volume in bytes.
+    pub fn total_bytes(&self) -> Option<u64> {
+        self.capacity.map(|capacity| capacity.total)
+    }
+
+    /// Returns the free space on the volume in bytes.
+    pub fn free_bytes(&self) -> Option<u64> {
+        self.capacity.map(|capacity| capacity.free)
+    }
+
+    /// Returns the free space available to the current user in bytes.
+    pub fn available_bytes(&self) -> Option<u64> {
+        self.capacity.map(|capacity| capacity.available)
+    }
+}
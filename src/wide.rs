@@ -0,0 +1,233 @@
+/*
+ * Copyright 2025 爱佐 (Ayrzo)
+ *
+ * This file is part of cargo crate samevol (https://crates.io/crates/samevol),
+ * which licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Helpers for the NUL-terminated UTF-16 buffers used by the Win32 API.
+//!
+//! Nothing in this module calls into the OS: the buffer growing logic takes the actual
+//! API call as a closure, and the multi-string parser works on plain slices. Both can
+//! therefore be tested (and fuzzed) on any platform.
+
+use std::io;
+
+/// Upper bound for buffers grown by [`fill_buffer`], in UTF-16 code units.
+///
+/// This is far above anything the Win32 API returns (paths are limited to 32767 code
+/// units), and only guards against an API that keeps asking for more.
+pub const MAX_BUFFER_LEN: usize = 1 << 24;
+
+/// Outcome of one attempt to fill a buffer, see [`fill_buffer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fill {
+    /// The call succeeded and wrote this many code units (the terminating NUL may or
+    /// may not be included, the buffer is truncated to this length either way).
+    Done(usize),
+    /// The buffer was too small. Contains the required length reported by the API, or
+    /// `0` if the API did not report one.
+    Grow(usize),
+}
+
+/// Calls `fill` with a buffer, growing and retrying as long as the buffer is too small.
+///
+/// Win32 functions report a too-small buffer in different ways (`ERROR_MORE_DATA`, a
+/// return value larger than the buffer, ...), so `fill` translates the outcome of the
+/// call into a [`Fill`]. When a retry is requested the buffer grows to the required
+/// length, but at least doubles, so the loop also terminates if the required length
+/// keeps changing between calls.
+///
+/// # Arguments
+/// - `initial_len` - The length of the first buffer, in code units
+/// - `fill` - Performs the call on the given buffer
+///
+/// # Returns
+/// The buffer truncated to the length reported by [`Fill::Done`].
+///
+/// # Errors
+/// Returns the error returned by `fill`, or an [`io::ErrorKind::OutOfMemory`] error if
+/// the buffer would have to grow beyond [`MAX_BUFFER_LEN`].
+pub fn fill_buffer<F>(initial_len: usize, mut fill: F) -> io::Result<Vec<u16>>
+where
+    F: FnMut(&mut [u16]) -> io::Result<Fill>,
+{
+    let mut buffer = vec![0u16; initial_len.clamp(1, MAX_BUFFER_LEN)];
+    loop {
+        match fill(&mut buffer)? {
+            Fill::Done(len) => {
+                buffer.truncate(len);
+                return Ok(buffer);
+            }
+            Fill::Grow(required) => {
+                // 至少翻倍，避免所需大小在两次调用之间变化时反复重试
+                let len = required.max(buffer.len().saturating_mul(2));
+                if len > MAX_BUFFER_LEN {
+                    return Err(io::Error::new(io::ErrorKind::OutOfMemory, "Buffer size limit exceeded"));
+                }
+                buffer.clear();
+                buffer.resize(len, 0);
+            }
+        }
+    }
+}
+
+/// Returns the part of `buffer` before the first NUL, or all of it if there is none.
+pub fn until_nul(buffer: &[u16]) -> &[u16] {
+    let end = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    &buffer[..end]
+}
+
+/// Splits a multi-string (a sequence of NUL-terminated strings, terminated by an empty
+/// string) as returned by `GetVolumePathNamesForVolumeNameW`.
+///
+/// Parsing never reads past the end of `buffer` and never fails: it stops at the first
+/// empty string, and a last string without terminating NUL extends to the end of the
+/// buffer.
+pub fn split_multi_sz(buffer: &[u16]) -> MultiSz<'_> {
+    MultiSz { rest: buffer }
+}
+
+/// Iterator over the strings of a multi-string, see [`split_multi_sz`].
+#[derive(Debug, Clone)]
+pub struct MultiSz<'a> {
+    rest: &'a [u16],
+}
+
+impl<'a> Iterator for MultiSz<'a> {
+    type Item = &'a [u16];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = until_nul(self.rest);
+        if item.is_empty() {
+            // 遇到空字符串（双重终止符）或缓冲区结尾
+            self.rest = &[];
+            return None;
+        }
+
+        self.rest = self.rest.get(item.len() + 1..).unwrap_or(&[]);
+        Some(item)
+    }
+}
+
+impl std::iter::FusedIterator for MultiSz<'_> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn wide(s: &str) -> Vec<u16> {
+        s.encode_utf16().collect()
+    }
+
+    fn split(buffer: &[u16]) -> Vec<String> {
+        split_multi_sz(buffer).map(String::from_utf16_lossy).collect()
+    }
+
+    #[test]
+    fn test_split_multi_sz() {
+        assert_eq!(split(&wide("C:\\\0D:\\mnt\\\0\0")), ["C:\\", "D:\\mnt\\"]);
+        assert_eq!(split(&wide("C:\\\0\0D:\\\0\0")), ["C:\\"]);
+        assert!(split(&wide("\0\0")).is_empty());
+        assert!(split(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_split_multi_sz_unterminated() {
+        // 缺少终止符时不越界读取
+        assert_eq!(split(&wide("C:\\\0D:\\")), ["C:\\", "D:\\"]);
+        assert_eq!(split(&wide("C:\\\0")), ["C:\\"]);
+        assert_eq!(split(&wide("C:\\")), ["C:\\"]);
+    }
+
+    #[test]
+    fn test_split_multi_sz_unpaired_surrogate() {
+        let buffer = [b'C' as u16, 0xD800, 0, b'D' as u16, 0, 0];
+        let items: Vec<&[u16]> = split_multi_sz(&buffer).collect();
+        assert_eq!(items, [&[b'C' as u16, 0xD800][..], &[b'D' as u16][..]]);
+    }
+
+    #[test]
+    fn test_split_multi_sz_random() {
+        // 简单的伪随机输入：结果拼接后应与缓冲区前缀一致，且每项非空、不含 NUL
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        for _ in 0..1000 {
+            let len = (next() % 64) as usize;
+            let buffer: Vec<u16> = (0..len).map(|_| if next() % 4 == 0 { 0 } else { next() as u16 }).collect();
+
+            let items: Vec<&[u16]> = split_multi_sz(&buffer).collect();
+            let mut joined = Vec::new();
+            for item in &items {
+                assert!(!item.is_empty() && !item.contains(&0));
+                joined.extend_from_slice(item);
+                joined.push(0);
+            }
+            let consumed = joined.len().min(buffer.len());
+            assert_eq!(joined[..consumed], buffer[..consumed]);
+        }
+    }
+
+    #[test]
+    fn test_until_nul() {
+        assert_eq!(until_nul(&wide("abc\0def")), wide("abc"));
+        assert_eq!(until_nul(&wide("abc")), wide("abc"));
+        assert!(until_nul(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_fill_buffer_grows_to_required_len() {
+        let source = wide(&"x".repeat(5000));
+        let mut calls = Vec::new();
+        let buffer = fill_buffer(261, |buffer| {
+            calls.push(buffer.len());
+            if buffer.len() <= source.len() {
+                return Ok(Fill::Grow(source.len() + 1));
+            }
+            buffer[..source.len()].copy_from_slice(&source);
+            Ok(Fill::Done(source.len()))
+        })
+        .unwrap();
+
+        assert_eq!(buffer, source);
+        assert_eq!(calls, [261, 5001]);
+    }
+
+    #[test]
+    fn test_fill_buffer_grows_without_required_len() {
+        let mut calls = Vec::new();
+        let buffer = fill_buffer(10, |buffer| {
+            calls.push(buffer.len());
+            Ok(if buffer.len() < 50 { Fill::Grow(0) } else { Fill::Done(3) })
+        })
+        .unwrap();
+
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(calls, [10, 20, 40, 80]);
+    }
+
+    #[test]
+    fn test_fill_buffer_errors() {
+        let err = fill_buffer(10, |_| Err(io::Error::from(io::ErrorKind::NotFound))).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+
+        let err = fill_buffer(10, |_| Ok(Fill::Grow(0))).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::OutOfMemory);
+    }
+}
@@ -0,0 +1,599 @@
+/*
+ * Copyright 2025 爱佐 (Ayrzo)
+ *
+ * This file is part of cargo crate samevol (https://crates.io/crates/samevol),
+ * which licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Windows 平台实现：基于 `FindFirstVolumeW` 等 API 构建卷映射表
+
+use std::ffi::{OsStr, OsString};
+use std::io;
+use std::os::windows::ffi::{OsStrExt as _, OsStringExt as _};
+use std::path::{Path, PathBuf};
+
+use crate::{VolumeBackend, VolumeInfo, VolumeMetadata};
+use crate::wide::{Fill, fill_buffer, split_multi_sz, until_nul};
+
+/// Windows API FFI绑定模块
+mod winapi {
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        // 卷管理相关 API
+
+        /// 查找第一个卷设备，返回搜索句柄
+        ///
+        /// # 参数
+        /// - `lpsz_volume_name`: 接收卷名的缓冲区。缓冲区应至少为 MAX_PATH+1 宽字符
+        /// - `cch_buffer_length`: 缓冲区大小（以宽字符计），包含终止空字符
+        ///
+        /// # 返回值
+        /// - 成功时返回搜索句柄
+        /// - 失败时返回 INVALID_HANDLE_VALUE
+        ///
+        /// # 安全性
+        /// 需要确保缓冲区足够大并有效
+        ///
+        /// [微软文档](https://docs.microsoft.com/en-us/windows/win32/api/fileapi/nf-fileapi-findfirstvolumew)
+        pub fn FindFirstVolumeW(
+            lpsz_volume_name: *mut u16,
+            cch_buffer_length: u32,
+        ) -> *mut std::ffi::c_void;
+
+        /// 查找下一个卷设备
+        ///
+        /// # 参数
+        /// - `h_find_volume`: 由 FindFirstVolumeW 返回的搜索句柄
+        /// - `lpsz_volume_name`: 接收卷名的缓冲区
+        /// - `cch_buffer_length`: 缓冲区大小（以宽字符计）
+        ///
+        /// # 返回值
+        /// - 成功返回非零值
+        /// - 失败返回 0（应调用 GetLastError 获取错误信息）
+        ///
+        /// # 安全性
+        /// 需要确保句柄有效且缓冲区足够大
+        ///
+        /// [微软文档](https://docs.microsoft.com/en-us/windows/win32/api/fileapi/nf-fileapi-findnextvolumew)
+        pub fn FindNextVolumeW(
+            h_find_volume: *mut std::ffi::c_void,
+            lpsz_volume_name: *mut u16,
+            cch_buffer_length: u32,
+        ) -> i32;
+
+        /// 关闭卷搜索句柄
+        ///
+        /// # 参数
+        /// - `h_find_volume`: 要关闭的搜索句柄
+        ///
+        /// # 返回值
+        /// - 成功返回非零值
+        /// - 失败返回 0
+        ///
+        /// # 安全性
+        /// 需要确保句柄有效且未被重复关闭
+        ///
+        /// [微软文档](https://docs.microsoft.com/en-us/windows/win32/api/fileapi/nf-fileapi-findvolumeclose)
+        pub fn FindVolumeClose(h_find_volume: *mut std::ffi::c_void) -> i32;
+
+        /// 获取指定卷的所有挂载点路径
+        ///
+        /// # 参数
+        /// - `lpsz_volume_name`: 输入卷名（GUID 格式），需以反斜杠结尾
+        /// - `lpsz_volume_path_names`: 接收路径列表的缓冲区（多个以空字符分隔的路径）
+        /// - `cch_buffer_length`: 缓冲区大小（以宽字符计）
+        /// - `pcch_return_length`: 接收实际需要的缓冲区大小（不含终止符）
+        ///
+        /// # 返回值
+        /// - 成功返回非零值
+        /// - 失败返回 0（若缓冲区不足，会返回 ERROR_MORE_DATA）
+        ///
+        /// # 安全性
+        /// 需要确保输入卷名格式正确，缓冲区足够大
+        ///
+        /// [微软文档](https://docs.microsoft.com/en-us/windows/win32/api/fileapi/nf-fileapi-getvolumepathnamesforvolumenamew)
+        pub fn GetVolumePathNamesForVolumeNameW(
+            lpsz_volume_name: *const u16,
+            lpsz_volume_path_names: *mut u16,
+            cch_buffer_length: u32,
+            pcch_return_length: *mut u32,
+        ) -> i32;
+
+        // 路径处理相关 API
+
+        /// 获取文件完整路径（展开相对路径和环境变量）
+        ///
+        /// # 参数
+        /// - `lp_file_name`: 输入路径（宽字符字符串）
+        /// - `n_buffer_length`: 输出缓冲区大小（宽字符数）
+        /// - `lp_buffer`: 接收完整路径的缓冲区
+        /// - `lp_file_part`: 接收文件名部分起始位置的指针（可为 null）
+        ///
+        /// # 返回值
+        /// - 成功返回复制到缓冲区的字符数（不含终止符）
+        /// - 若缓冲区不足，返回所需缓冲区大小（含终止符）
+        /// - 失败返回 0
+        ///
+        /// # 安全性
+        /// 需要确保输入指针有效，缓冲区足够大
+        ///
+        /// [微软文档](https://docs.microsoft.com/en-us/windows/win32/api/fileapi/nf-fileapi-getfullpathnamew)
+        pub fn GetFullPathNameW(
+            lp_file_name: *const u16,
+            n_buffer_length: u32,
+            lp_buffer: *mut u16,
+            lp_file_part: *mut *mut u16,
+        ) -> u32;
+
+        /// 获取路径所属的卷挂载点
+        ///
+        /// # 参数
+        /// - `lpsz_file_name`: 输入文件路径（宽字符字符串）
+        /// - `lpsz_volume_path_name`: 输出挂载点路径的缓冲区
+        /// - `cch_buffer_length`: 缓冲区大小（宽字符数）
+        ///
+        /// # 返回值
+        /// - 成功返回非零值
+        /// - 失败返回 0
+        ///
+        /// # 安全性
+        /// 需要确保缓冲区足够大（通常至少 MAX_PATH 长度）
+        ///
+        /// [微软文档](https://docs.microsoft.com/en-us/windows/win32/api/fileapi/nf-fileapi-getvolumepathnamew)
+        pub fn GetVolumePathNameW(
+            lpsz_file_name: *const u16,
+            lpsz_volume_path_name: *mut u16,
+            cch_buffer_length: u32,
+        ) -> i32;
+
+        // 卷信息相关 API
+
+        /// 获取卷的文件系统信息
+        ///
+        /// # 参数
+        /// - `lp_root_path_name`: 卷根目录（驱动器号、挂载文件夹或卷 GUID 路径），需以反斜杠结尾
+        /// - `lp_volume_name_buffer`: 接收卷标的缓冲区（可为 null）
+        /// - `n_volume_name_size`: 卷标缓冲区大小（宽字符数），最大 MAX_PATH + 1
+        /// - `lp_volume_serial_number`: 接收卷序列号（可为 null）
+        /// - `lp_maximum_component_length`: 接收文件名组件的最大长度（可为 null）
+        /// - `lp_file_system_flags`: 接收文件系统标志（可为 null）
+        /// - `lp_file_system_name_buffer`: 接收文件系统名称的缓冲区（可为 null）
+        /// - `n_file_system_name_size`: 文件系统名称缓冲区大小（宽字符数），最大 MAX_PATH + 1
+        ///
+        /// # 返回值
+        /// - 成功返回非零值
+        /// - 失败返回 0（如可移动驱动器中没有介质）
+        ///
+        /// # 安全性
+        /// 需要确保输入路径以空字符结尾，缓冲区大小与实际一致
+        ///
+        /// [微软文档](https://docs.microsoft.com/en-us/windows/win32/api/fileapi/nf-fileapi-getvolumeinformationw)
+        pub fn GetVolumeInformationW(
+            lp_root_path_name: *const u16,
+            lp_volume_name_buffer: *mut u16,
+            n_volume_name_size: u32,
+            lp_volume_serial_number: *mut u32,
+            lp_maximum_component_length: *mut u32,
+            lp_file_system_flags: *mut u32,
+            lp_file_system_name_buffer: *mut u16,
+            n_file_system_name_size: u32,
+        ) -> i32;
+
+        /// 获取卷的容量和可用空间
+        ///
+        /// # 参数
+        /// - `lp_directory_name`: 卷上的目录（可为卷 GUID 路径），需以反斜杠结尾
+        /// - `lp_free_bytes_available_to_caller`: 接收调用者可用的字节数（考虑配额）
+        /// - `lp_total_number_of_bytes`: 接收调用者可见的总字节数
+        /// - `lp_total_number_of_free_bytes`: 接收卷上的总空闲字节数
+        ///
+        /// # 返回值
+        /// - 成功返回非零值
+        /// - 失败返回 0
+        ///
+        /// # 安全性
+        /// 需要确保输入路径以空字符结尾，输出指针有效
+        ///
+        /// [微软文档](https://docs.microsoft.com/en-us/windows/win32/api/fileapi/nf-fileapi-getdiskfreespaceexw)
+        pub fn GetDiskFreeSpaceExW(
+            lp_directory_name: *const u16,
+            lp_free_bytes_available_to_caller: *mut u64,
+            lp_total_number_of_bytes: *mut u64,
+            lp_total_number_of_free_bytes: *mut u64,
+        ) -> i32;
+
+        // 设备访问相关 API
+
+        /// 打开文件或设备，返回句柄
+        ///
+        /// # 参数
+        /// - `lp_file_name`: 文件或设备名（如 `\\?\Volume{...}`，不含结尾反斜杠）
+        /// - `dw_desired_access`: 访问权限，为 0 时仅查询设备属性
+        /// - `dw_share_mode`: 共享模式
+        /// - `lp_security_attributes`: 安全属性（可为 null）
+        /// - `dw_creation_disposition`: 打开方式，设备须为 OPEN_EXISTING
+        /// - `dw_flags_and_attributes`: 文件属性和标志
+        /// - `h_template_file`: 模板文件句柄（可为 null）
+        ///
+        /// # 返回值
+        /// - 成功时返回句柄
+        /// - 失败时返回 INVALID_HANDLE_VALUE
+        ///
+        /// # 安全性
+        /// 需要确保输入路径以空字符结尾，返回的句柄须由 CloseHandle 关闭
+        ///
+        /// [微软文档](https://docs.microsoft.com/en-us/windows/win32/api/fileapi/nf-fileapi-createfilew)
+        pub fn CreateFileW(
+            lp_file_name: *const u16,
+            dw_desired_access: u32,
+            dw_share_mode: u32,
+            lp_security_attributes: *mut std::ffi::c_void,
+            dw_creation_disposition: u32,
+            dw_flags_and_attributes: u32,
+            h_template_file: *mut std::ffi::c_void,
+        ) -> *mut std::ffi::c_void;
+
+        /// 向设备驱动发送控制码
+        ///
+        /// # 参数
+        /// - `h_device`: 设备句柄
+        /// - `dw_io_control_code`: 控制码
+        /// - `lp_in_buffer`: 输入缓冲区（可为 null）
+        /// - `n_in_buffer_size`: 输入缓冲区大小（字节）
+        /// - `lp_out_buffer`: 输出缓冲区
+        /// - `n_out_buffer_size`: 输出缓冲区大小（字节）
+        /// - `lp_bytes_returned`: 接收写入输出缓冲区的字节数
+        /// - `lp_overlapped`: 异步操作结构（同步调用时为 null）
+        ///
+        /// # 返回值
+        /// - 成功返回非零值
+        /// - 失败返回 0（若输出缓冲区不足，会返回 ERROR_MORE_DATA）
+        ///
+        /// # 安全性
+        /// 需要确保句柄有效，缓冲区大小与实际一致
+        ///
+        /// [微软文档](https://docs.microsoft.com/en-us/windows/win32/api/ioapiset/nf-ioapiset-deviceiocontrol)
+        pub fn DeviceIoControl(
+            h_device: *mut std::ffi::c_void,
+            dw_io_control_code: u32,
+            lp_in_buffer: *mut std::ffi::c_void,
+            n_in_buffer_size: u32,
+            lp_out_buffer: *mut std::ffi::c_void,
+            n_out_buffer_size: u32,
+            lp_bytes_returned: *mut u32,
+            lp_overlapped: *mut std::ffi::c_void,
+        ) -> i32;
+
+        /// 关闭句柄
+        ///
+        /// # 参数
+        /// - `h_object`: 要关闭的句柄
+        ///
+        /// # 返回值
+        /// - 成功返回非零值
+        /// - 失败返回 0
+        ///
+        /// # 安全性
+        /// 需要确保句柄有效且未被重复关闭
+        ///
+        /// [微软文档](https://docs.microsoft.com/en-us/windows/win32/api/handleapi/nf-handleapi-closehandle)
+        pub fn CloseHandle(h_object: *mut std::ffi::c_void) -> i32;
+    }
+}
+
+/// Windows API调用结果类型别名
+type WinResult<T> = Result<T, io::Error>;
+
+/// `FindFirstVolumeW` 失败时返回的无效句柄
+const INVALID_HANDLE_VALUE: *mut std::ffi::c_void = -1isize as *mut std::ffi::c_void;
+/// 枚举结束
+const ERROR_NO_MORE_FILES: i32 = 18;
+/// 缓冲区不足
+const ERROR_INSUFFICIENT_BUFFER: i32 = 122;
+/// 文件名或扩展名过长
+const ERROR_FILENAME_EXCED_RANGE: i32 = 206;
+/// 还有更多数据
+const ERROR_MORE_DATA: i32 = 234;
+/// 首次尝试的路径缓冲区大小（MAX_PATH + 1），不足时按需扩大
+const INITIAL_PATH_LEN: usize = 261;
+/// 允许其他进程读写（打开卷设备时须指定）
+const FILE_SHARE_READ_WRITE: u32 = 0x1 | 0x2;
+/// 仅打开已存在的文件或设备
+const OPEN_EXISTING: u32 = 3;
+/// 查询卷所在的磁盘区段
+const IOCTL_VOLUME_GET_VOLUME_DISK_EXTENTS: u32 = 0x0056_0000;
+/// `VOLUME_DISK_EXTENTS` 头部大小（区段数 + 对齐填充）
+const DISK_EXTENTS_HEADER_LEN: usize = 8;
+/// `DISK_EXTENT` 大小：磁盘号（含填充）8 + 起始偏移 8 + 长度 8
+const DISK_EXTENT_LEN: usize = 24;
+
+/// 将操作系统字符串转换为Windows宽字符字符串（保留未配对的代理项）
+fn wide_string(s: &OsStr) -> Vec<u16> {
+    s.encode_wide()     // 转换为 UTF-16 编码迭代器
+        .chain(Some(0)) // 追加终止符
+        .collect()      // collect as Vec<u16>
+}
+
+/// 从宽字符缓冲区读取终止字符串
+///
+/// 仅用于卷 GUID 路径等必然为有效 UTF-16 的字符串。
+fn from_wide_buf(buffer: &[u16]) -> WinResult<String> {
+    // 转换为UTF-8字符串
+    String::from_utf16(until_nul(buffer))
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "UTF-16 conversion failed"))
+}
+
+/// 将宽字符挂载点路径规范化为映射表键：统一使用反斜杠并确保结尾反斜杠
+fn mount_point_from_wide(buffer: &[u16]) -> OsString {
+    const SLASH: u16 = b'/' as u16;
+    const BACKSLASH: u16 = b'\\' as u16;
+
+    let mut path: Vec<u16> = until_nul(buffer)
+        .iter()
+        .map(|&c| if c == SLASH { BACKSLASH } else { c })
+        .collect();
+    if path.last() != Some(&BACKSLASH) {
+        path.push(BACKSLASH); // 追加反斜杠用于前缀匹配
+    }
+    OsString::from_wide(&path)
+}
+
+/// Windows volume backend built on the Win32 volume management API.
+///
+/// Volumes are enumerated with `FindFirstVolumeW`/`FindNextVolumeW`, mount points are
+/// listed with `GetVolumePathNamesForVolumeNameW`, and paths are resolved with
+/// `GetFullPathNameW` and `GetVolumePathNameW`. Volume metadata is queried with
+/// `GetVolumeInformationW` and `GetDiskFreeSpaceExW` on the volume GUID path, so volumes
+/// without any mount point are supported as well.
+///
+/// Physical disks are found with `IOCTL_VOLUME_GET_VOLUME_DISK_EXTENTS` and identified as
+/// `PhysicalDrive<N>`, the name under which the disk can be opened as `\\.\PhysicalDrive<N>`.
+/// A volume on a mounted VHD(X) is on the virtual disk, not on the disk holding the file.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WindowsBackend;
+
+impl VolumeBackend for WindowsBackend {
+    fn volumes(&self) -> io::Result<Vec<(String, Vec<OsString>)>> {
+        let mut volumes = Vec::new();
+
+        /* 卷名缓冲区说明（格式见 `crate::guid`）：
+         * 格式：`\\?\Volume{xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx}\`
+         * 总长度：4(前缀`\\?\`) + 7(`Volume{`) + 36(GUID) + 2(`}\`) + 1(`\0`) = 50 个宽字符
+         */
+        let mut buffer = [0u16; 50];
+
+        // 启动卷枚举
+        let handle = unsafe { winapi::FindFirstVolumeW(buffer.as_mut_ptr(), buffer.len() as u32) };
+        if handle == INVALID_HANDLE_VALUE {
+            return Err(io::Error::last_os_error());
+        }
+
+        // 遍历所有卷设备
+        let result = loop {
+            // 转换当前卷名
+            let volume_name = match from_wide_buf(&buffer) {
+                Ok(volume_name) => volume_name,
+                Err(e) => break Err(e),
+            };
+
+            // 获取该卷的所有挂载点路径；卷可能在枚举期间被移除，此时视为没有挂载点
+            let mount_points = volume_path_names(&buffer)
+                .map(|paths| split_multi_sz(&paths).map(mount_point_from_wide).collect())
+                .unwrap_or_default();
+            volumes.push((volume_name, mount_points));
+
+            // 获取下一个卷
+            let next = unsafe {
+                buffer.fill(0); // 清空缓冲区
+                winapi::FindNextVolumeW(handle, buffer.as_mut_ptr(), buffer.len() as u32)
+            };
+            if next == 0 {
+                // 区分枚举完成与出错
+                let error = io::Error::last_os_error();
+                break match error.raw_os_error() {
+                    Some(ERROR_NO_MORE_FILES) => Ok(()),
+                    _ => Err(error),
+                };
+            }
+        };
+
+        // 关闭卷搜索句柄
+        unsafe { winapi::FindVolumeClose(handle) };
+        result.map(|()| volumes)
+    }
+
+    fn full_path(&self, path: &Path) -> io::Result<PathBuf> {
+        // 转换为宽字符路径
+        let path_wide = wide_string(path.as_os_str());
+
+        let full_path = fill_buffer(INITIAL_PATH_LEN, |buffer| {
+            let len = unsafe {
+                winapi::GetFullPathNameW(
+                    path_wide.as_ptr(),   // 输入路径
+                    buffer.len() as u32,  // 输出缓冲区大小
+                    buffer.as_mut_ptr(),  // 输出缓冲区
+                    std::ptr::null_mut(), // 不需要文件名部分
+                )
+            } as usize;
+            match len {
+                0 => Err(io::Error::last_os_error()),
+                // 缓冲区不足时返回所需大小（含终止符）
+                len if len >= buffer.len() => Ok(Fill::Grow(len)),
+                len => Ok(Fill::Done(len)),
+            }
+        })?;
+
+        Ok(PathBuf::from(OsString::from_wide(&full_path)))
+    }
+
+    fn volume_mount_point(&self, full_path: &Path) -> io::Result<OsString> {
+        let full_path_wide = wide_string(full_path.as_os_str());
+        // 挂载点通常是路径自身的前缀，加上结尾反斜杠和终止符即可容纳
+        let initial_len = (full_path_wide.len() + 1).max(INITIAL_PATH_LEN);
+
+        let mount_point = fill_buffer(initial_len, |buffer| {
+            let success = unsafe {
+                winapi::GetVolumePathNameW(
+                    full_path_wide.as_ptr(), // 输入绝对路径
+                    buffer.as_mut_ptr(),     // 输出挂载点路径
+                    buffer.len() as u32,     // 缓冲区大小
+                )
+            };
+            if success != 0 {
+                return Ok(Fill::Done(buffer.len()));
+            }
+
+            // 该 API 不返回所需大小，缓冲区不足时直接扩大重试
+            let error = io::Error::last_os_error();
+            match error.raw_os_error() {
+                Some(ERROR_INSUFFICIENT_BUFFER | ERROR_FILENAME_EXCED_RANGE) => Ok(Fill::Grow(0)),
+                _ => Err(error),
+            }
+        })?;
+
+        // 转换结果并确保以反斜杠结尾
+        Ok(mount_point_from_wide(&mount_point))
+    }
+
+    fn physical_disks(&self, volume: &VolumeInfo) -> io::Result<Vec<String>> {
+        // 打开卷设备时不能带结尾反斜杠，否则打开的是卷的根目录
+        let name = wide_string(OsStr::new(volume.id().as_str().trim_end_matches('\\')));
+        let handle = unsafe {
+            winapi::CreateFileW(
+                name.as_ptr(),            // 卷设备名
+                0,                        // 仅查询属性，无需读写权限
+                FILE_SHARE_READ_WRITE,    // 卷已被文件系统打开，必须共享
+                std::ptr::null_mut(),     // 默认安全属性
+                OPEN_EXISTING,            // 设备只能打开已存在的
+                0,                        // 无特殊标志
+                std::ptr::null_mut(),     // 无模板文件
+            )
+        };
+        if handle == INVALID_HANDLE_VALUE {
+            return Err(io::Error::last_os_error());
+        }
+
+        let result = volume_disk_extents(handle);
+        unsafe { winapi::CloseHandle(handle) };
+
+        // 同一磁盘上可能有多个区段，按出现顺序去重
+        let mut disks: Vec<String> = Vec::new();
+        for disk_number in result? {
+            let disk = format!("PhysicalDrive{}", disk_number);
+            if !disks.contains(&disk) {
+                disks.push(disk);
+            }
+        }
+        Ok(disks)
+    }
+
+    fn volume_metadata(&self, volume: &VolumeInfo) -> io::Result<VolumeMetadata> {
+        // 卷 GUID 路径本身即可作为根目录，无需挂载点
+        let root = wide_string(OsStr::new(volume.id().as_str()));
+
+        let mut label = [0u16; INITIAL_PATH_LEN];
+        let mut fs_name = [0u16; INITIAL_PATH_LEN];
+        let mut serial_number = 0u32;
+        let success = unsafe {
+            winapi::GetVolumeInformationW(
+                root.as_ptr(),             // 卷根目录
+                label.as_mut_ptr(),        // 输出卷标
+                label.len() as u32,        // 卷标缓冲区大小
+                &mut serial_number,        // 输出序列号
+                std::ptr::null_mut(),      // 不需要最大组件长度
+                std::ptr::null_mut(),      // 不需要文件系统标志
+                fs_name.as_mut_ptr(),      // 输出文件系统名称
+                fs_name.len() as u32,      // 文件系统名称缓冲区大小
+            )
+        };
+        if success == 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let (mut available, mut total, mut free) = (0u64, 0u64, 0u64);
+        let success = unsafe { winapi::GetDiskFreeSpaceExW(root.as_ptr(), &mut available, &mut total, &mut free) };
+        if success == 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(VolumeMetadata::new()
+            .with_fs_type(String::from_utf16_lossy(until_nul(&fs_name)))
+            .with_label(String::from_utf16_lossy(until_nul(&label)))
+            .with_serial_number(serial_number)
+            .with_capacity(total, free, available))
+    }
+}
+
+/// 查询卷所在的各区段的磁盘号；跨多个磁盘的卷（动态磁盘、存储空间）在缓冲区不足时按区段数重试
+fn volume_disk_extents(handle: *mut std::ffi::c_void) -> io::Result<Vec<u32>> {
+    // 大多数卷只有一个区段
+    let mut buffer = vec![0u8; DISK_EXTENTS_HEADER_LEN + DISK_EXTENT_LEN];
+    loop {
+        let mut returned_len = 0;
+        let success = unsafe {
+            winapi::DeviceIoControl(
+                handle,                                   // 卷设备句柄
+                IOCTL_VOLUME_GET_VOLUME_DISK_EXTENTS,     // 控制码
+                std::ptr::null_mut(),                     // 无输入
+                0,                                        // 输入大小
+                buffer.as_mut_ptr().cast(),               // 输出 VOLUME_DISK_EXTENTS
+                buffer.len() as u32,                      // 输出缓冲区大小
+                &mut returned_len,                        // 实际写入字节数
+                std::ptr::null_mut(),                     // 同步调用
+            )
+        };
+
+        let count = u32::from_le_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]) as usize;
+        let required = DISK_EXTENTS_HEADER_LEN + count * DISK_EXTENT_LEN;
+        if success != 0 {
+            if (returned_len as usize) < required.min(buffer.len()) {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "truncated disk extents"));
+            }
+            return Ok(buffer[DISK_EXTENTS_HEADER_LEN..required]
+                .chunks_exact(DISK_EXTENT_LEN)
+                .map(|extent| u32::from_le_bytes([extent[0], extent[1], extent[2], extent[3]]))
+                .collect());
+        }
+
+        let error = io::Error::last_os_error();
+        match error.raw_os_error() {
+            // 头部已写入区段总数
+            Some(ERROR_MORE_DATA) if required > buffer.len() => buffer.resize(required, 0),
+            _ => return Err(error),
+        }
+    }
+}
+
+/// 获取卷的所有挂载点路径（多重终止字符串），缓冲区不足时按所需大小重试
+fn volume_path_names(volume_name: &[u16]) -> io::Result<Vec<u16>> {
+    fill_buffer(INITIAL_PATH_LEN, |buffer| {
+        let mut returned_len = 0;
+        let success = unsafe {
+            winapi::GetVolumePathNamesForVolumeNameW(
+                volume_name.as_ptr(),  // 输入卷名
+                buffer.as_mut_ptr(),   // 输出路径列表
+                buffer.len() as u32,   // 缓冲区大小
+                &mut returned_len,     // 接收实际需要大小
+            )
+        };
+        if success != 0 {
+            return Ok(Fill::Done(returned_len as usize));
+        }
+
+        let error = io::Error::last_os_error();
+        match error.raw_os_error() {
+            Some(ERROR_MORE_DATA) => Ok(Fill::Grow(returned_len as usize)),
+            _ => Err(error),
+        }
+    })
+}
@@ -0,0 +1,309 @@
+/*
+ * Copyright 2025 爱佐 (Ayrzo)
+ *
+ * This file is part of cargo crate samevol (https://crates.io/crates/samevol),
+ * which licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Windows path semantics, implemented in pure Rust.
+//!
+//! [`WorkingDirs::full_path`] reproduces the normalisation `GetFullPathNameW` performs,
+//! against an explicitly supplied current directory and per-drive current directory
+//! table instead of the process state. This makes the way paths are expanded before
+//! volume resolution predictable, and testable on any OS:
+//!
+//! - `/` is accepted as a separator and converted to `\`, runs of separators collapse
+//! - `.` segments are removed and `..` segments remove the previous segment, but never
+//!   climb above the root (`C:\`, `\\server\share`, `\\.\device`)
+//! - a single trailing period is removed from every segment, and trailing periods and
+//!   spaces are removed from the last segment unless the path ends with a separator
+//! - relative, drive-relative (`C:foo`) and rooted (`\foo`) paths are resolved against
+//!   the current directories
+//! - `\\?\` paths are returned unchanged
+//!
+//! Legacy device names such as `CON` or `NUL`, which some Windows versions map to
+//! `\\.\CON`, are not emulated and are treated as ordinary file names.
+//!
+//! Paths are processed on their encoded bytes, so paths that are not valid Unicode are
+//! supported and round-trip unchanged.
+//!
+//! # Example
+//! ```rust
+//! use std::path::Path;
+//! use samevol::winpath::WorkingDirs;
+//!
+//! let dirs = WorkingDirs::new(r"C:\Users\Public").with_drive_dir('D', r"D:\data");
+//! assert_eq!(dirs.full_path(r"..\Admin\.\file.txt. ").unwrap(), Path::new(r"C:\Users\Admin\file.txt"));
+//! assert_eq!(dirs.full_path("D:logs/today").unwrap(), Path::new(r"D:\data\logs\today"));
+//! assert_eq!(dirs.full_path(r"E:x\..\..").unwrap(), Path::new(r"E:\"));
+//! ```
+
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The type of a Windows path, as determined by its prefix.
+///
+/// This mirrors the classification `GetFullPathNameW` uses to decide how a path is
+/// completed and where its root ends. Both `\` and `/` are accepted as separators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PathType {
+    /// `\\?\C:\foo`: a verbatim path, which is never normalised.
+    Verbatim,
+    /// `\\.\C:\foo` or `//?/C:/foo`: a device path, rooted at its first segment.
+    Device,
+    /// `\\server\share\foo`: a UNC path, rooted at the share.
+    Unc,
+    /// `C:\foo`: an absolute path on a drive.
+    DriveAbsolute,
+    /// `C:foo`: a path relative to the current directory of a drive.
+    DriveRelative,
+    /// `\foo`: a path relative to the root of the current directory.
+    Rooted,
+    /// `foo`: a path relative to the current directory.
+    Relative,
+}
+
+impl PathType {
+    /// Determines the type of `path`.
+    pub fn of<P: AsRef<Path>>(path: P) -> Self {
+        path_type(path.as_ref().as_os_str().as_encoded_bytes())
+    }
+
+    /// Returns `true` if paths of this type do not depend on any current directory.
+    pub fn is_absolute(self) -> bool {
+        !matches!(self, PathType::DriveRelative | PathType::Rooted | PathType::Relative)
+    }
+}
+
+/// The current directory and per-drive current directories used to complete relative
+/// paths, see the [module documentation](self).
+///
+/// On Windows, the process has one current directory, and the command prompt keeps the
+/// last directory used on every other drive in the hidden `=C:`, `=D:`, ... environment
+/// variables. A drive-relative path such as `D:foo` is resolved against the current
+/// directory if it is on drive `D`, otherwise against the directory recorded for `D`,
+/// otherwise against `D:\`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkingDirs {
+    current_dir: Vec<u8>,
+    drive_dirs: HashMap<u8, Vec<u8>>,
+}
+
+impl WorkingDirs {
+    /// Creates a table with the given current directory and no per-drive directories.
+    ///
+    /// # Arguments
+    /// - `current_dir` - A full path, such as `C:\Users` or `\\server\share\dir`
+    pub fn new<P: AsRef<Path>>(current_dir: P) -> Self {
+        WorkingDirs {
+            current_dir: encoded_bytes(current_dir.as_ref()),
+            drive_dirs: HashMap::new(),
+        }
+    }
+
+    /// Records the current directory of `drive`, used for drive-relative paths on a
+    /// drive other than the one of the current directory.
+    pub fn with_drive_dir<P: AsRef<Path>>(mut self, drive: char, dir: P) -> Self {
+        self.set_drive_dir(drive, dir);
+        self
+    }
+
+    /// Returns the current directory.
+    pub fn current_dir(&self) -> &Path {
+        Path::new(bytes_to_os_str(&self.current_dir))
+    }
+
+    /// Changes the current directory.
+    pub fn set_current_dir<P: AsRef<Path>>(&mut self, dir: P) {
+        self.current_dir = encoded_bytes(dir.as_ref());
+    }
+
+    /// Records the current directory of `drive`, see [`with_drive_dir`](Self::with_drive_dir).
+    ///
+    /// Characters other than ASCII letters are ignored, as they are not valid drives.
+    pub fn set_drive_dir<P: AsRef<Path>>(&mut self, drive: char, dir: P) {
+        if drive.is_ascii_alphabetic() {
+            self.drive_dirs.insert(drive.to_ascii_uppercase() as u8, encoded_bytes(dir.as_ref()));
+        }
+    }
+
+    /// Expands `path` to a full path the way `GetFullPathNameW` does.
+    ///
+    /// The path does not need to exist, and nothing touches the file system.
+    ///
+    /// # Errors
+    /// Returns an [`io::ErrorKind::InvalidInput`] error if `path` is empty.
+    pub fn full_path<P: AsRef<Path>>(&self, path: P) -> io::Result<PathBuf> {
+        let path = path.as_ref().as_os_str().as_encoded_bytes();
+        let full_path = full_path(path, &self.current_dir, |drive| {
+            self.drive_dirs.get(&drive.to_ascii_uppercase()).map(Vec::as_slice)
+        })?;
+        Ok(PathBuf::from(into_os_string(full_path)))
+    }
+}
+
+/// 按 `GetFullPathNameW` 的规则展开路径（按编码字节处理）
+///
+/// `drive_dir` 返回其他驱动器的当前目录（驱动器号为 ASCII 字母）。
+pub(crate) fn full_path<'a, F>(path: &[u8], current_dir: &'a [u8], drive_dir: F) -> io::Result<Vec<u8>>
+where
+    F: Fn(u8) -> Option<&'a [u8]>,
+{
+    if path.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "empty path"));
+    }
+
+    // 按路径类型拼接当前目录，得到绝对路径
+    let joined = match path_type(path) {
+        PathType::Verbatim => return Ok(path.to_vec()),
+        PathType::Device | PathType::Unc | PathType::DriveAbsolute => path.to_vec(),
+        PathType::DriveRelative => {
+            let drive = path[0];
+            let drive_root = [drive, b':', b'\\'];
+            let base = match current_drive(current_dir) {
+                Some(current) if current.eq_ignore_ascii_case(&drive) => current_dir,
+                _ => drive_dir(drive).unwrap_or(&drive_root),
+            };
+            [base, b"\\", &path[2..]].concat()
+        }
+        PathType::Rooted => {
+            let current_dir = with_backslashes(current_dir);
+            let root_len = root_len(&current_dir);
+            [&current_dir[..root_len], path].concat()
+        }
+        PathType::Relative => [current_dir, b"\\", path].concat(),
+    };
+
+    let joined = with_backslashes(&joined);
+    let root_len = root_len(&joined);
+    let (root, rest) = joined.split_at(root_len);
+
+    // 处理 `.`、`..` 并去除结尾的点号
+    let mut segments: Vec<&[u8]> = Vec::new();
+    for segment in rest.split(|&b| b == b'\\') {
+        match segment {
+            b"" | b"." => {}
+            b".." => {
+                segments.pop();
+            }
+            segment if segment.ends_with(b".") && !segment.ends_with(b"..") => {
+                segments.push(&segment[..segment.len() - 1]);
+            }
+            segment => segments.push(segment),
+        }
+    }
+
+    // 不以分隔符结尾时，去除最后一段结尾的所有点号和空格
+    let trailing_separator = matches!(path.last(), Some(b'\\' | b'/'));
+    if !trailing_separator && let Some(last) = segments.pop() {
+        let end = last.iter().rposition(|&b| b != b'.' && b != b' ').map_or(0, |i| i + 1);
+        if end > 0 {
+            segments.push(&last[..end]);
+        }
+    }
+
+    let mut full_path = root.to_vec();
+    for segment in &segments {
+        if full_path.last() != Some(&b'\\') {
+            full_path.push(b'\\');
+        }
+        full_path.extend_from_slice(segment);
+    }
+    if (trailing_separator || segments.is_empty() && !rest.is_empty()) && full_path.last() != Some(&b'\\') {
+        full_path.push(b'\\');
+    }
+    Ok(full_path)
+}
+
+/// 判断路径类型（对应 `RtlDetermineDosPathNameType_U`）
+fn path_type(path: &[u8]) -> PathType {
+    let is_separator = |i: usize| path.get(i).is_some_and(|&b| b == b'\\' || b == b'/');
+
+    if path.starts_with(br"\\?\") {
+        PathType::Verbatim
+    } else if is_separator(0) && is_separator(1) {
+        match path.get(2) {
+            Some(b'.' | b'?') if is_separator(3) || path.len() == 3 => PathType::Device,
+            _ => PathType::Unc,
+        }
+    } else if is_separator(0) {
+        PathType::Rooted
+    } else if path.len() >= 2 && path[0].is_ascii_alphabetic() && path[1] == b':' {
+        if is_separator(2) { PathType::DriveAbsolute } else { PathType::DriveRelative }
+    } else {
+        PathType::Relative
+    }
+}
+
+/// 计算已统一为反斜杠的绝对路径的根长度（`C:\`、`\\server\share`、`\\.\device`、`\`）
+fn root_len(path: &[u8]) -> usize {
+    // 第 n 个分隔符之后的位置，不足时为路径长度
+    let segment_end = |start: usize, count: usize| {
+        let mut end = start;
+        for _ in 0..count {
+            end = match path[end.min(path.len())..].iter().position(|&b| b == b'\\') {
+                Some(i) => end + i + 1,
+                None => return path.len(),
+            };
+        }
+        end - 1
+    };
+
+    match path_type(path) {
+        PathType::Verbatim | PathType::Device => segment_end(4.min(path.len()), 1),
+        PathType::Unc => segment_end(2, 2),
+        PathType::DriveAbsolute => 3,
+        PathType::DriveRelative => 2,
+        PathType::Rooted => 1,
+        PathType::Relative => 0,
+    }
+}
+
+/// 当前目录所在的驱动器号
+fn current_drive(current_dir: &[u8]) -> Option<u8> {
+    (current_dir.len() >= 2 && current_dir[0].is_ascii_alphabetic() && current_dir[1] == b':')
+        .then_some(current_dir[0])
+}
+
+/// 统一使用反斜杠，并合并连续的分隔符（UNC 和设备路径开头的两个除外）
+fn with_backslashes(path: &[u8]) -> Vec<u8> {
+    let leading = if matches!(path_type(path), PathType::Device | PathType::Unc) { 2 } else { 0 };
+    let mut result = Vec::with_capacity(path.len());
+    for (i, &b) in path.iter().enumerate() {
+        let b = if b == b'/' { b'\\' } else { b };
+        if b == b'\\' && i >= leading && result.last() == Some(&b'\\') {
+            continue;
+        }
+        result.push(b);
+    }
+    result
+}
+
+fn encoded_bytes(path: &Path) -> Vec<u8> {
+    path.as_os_str().as_encoded_bytes().to_vec()
+}
+
+fn bytes_to_os_str(bytes: &[u8]) -> &OsStr {
+    // SAFETY: 字节均来自 `OsStr::as_encoded_bytes`
+    unsafe { OsStr::from_encoded_bytes_unchecked(bytes) }
+}
+
+/// 将编码字节转换回 `OsString`
+pub(crate) fn into_os_string(bytes: Vec<u8>) -> OsString {
+    // SAFETY: 字节均来自 `OsStr::as_encoded_bytes`，且只在 ASCII 字符处拆分和拼接，
+    // 符合 `from_encoded_bytes_unchecked` 的要求
+    unsafe { OsStr::from_encoded_bytes_unchecked(&bytes) }.to_owned()
+}
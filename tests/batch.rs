@@ -0,0 +1,141 @@
+#[cfg(test)]
+mod test {
+    use std::io;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use samevol::*;
+
+    fn layout() -> Arc<FakeBackend> {
+        Arc::new(
+            FakeBackend::windows()
+                .with_volume("C", [r"C:\"])
+                .with_volume("D", [r"D:\"])
+                .with_volume("W", [r"D:\Vdisks\Wechat"])
+                .with_path_error(r"C:\missing", io::ErrorKind::NotFound),
+        )
+    }
+
+    #[test]
+    fn test_resolve_many() {
+        let resolver = Resolver::builder().backend(layout()).build();
+        let results = resolver.resolve_many([
+            r"C:\a",
+            r"D:\Vdisks\file",
+            r"D:\Vdisks\Wechat",
+            r"D:\Vdisks\Wechat\file",
+            r"C:\missing",
+            r"E:\x",
+            r"C:\b",
+        ]);
+
+        let ids: Vec<Option<&str>> = results.iter().map(|r| r.as_ref().ok().map(VolumeId::as_str)).collect();
+        assert_eq!(ids, [Some("C"), Some("D"), Some("W"), Some("W"), None, None, Some("C")]);
+        assert!(matches!(&results[4], Err(Error::NotFound { path, .. }) if path.as_os_str() == r"C:\missing"));
+        assert!(matches!(&results[5], Err(Error::InvalidPath { .. } | Error::NotFound { .. })));
+    }
+
+    #[test]
+    fn test_group_by_volume() {
+        let resolver = Resolver::builder().backend(layout()).build();
+        let groups = resolver.group_by_volume([r"C:\a", r"D:\b", r"C:\c", r"D:\Vdisks\Wechat\d", r"C:\missing"]);
+
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups["C"], [PathBuf::from(r"C:\a"), PathBuf::from(r"C:\c")]);
+        assert_eq!(groups["D"], [PathBuf::from(r"D:\b")]);
+        assert_eq!(groups["W"], [PathBuf::from(r"D:\Vdisks\Wechat\d")]);
+    }
+
+    #[test]
+    fn test_batch_failed_init() {
+        let backend = layout();
+        backend.fail_enumeration(io::ErrorKind::PermissionDenied);
+        let resolver = Resolver::builder().backend(backend.clone()).build();
+
+        let results = resolver.resolve_many([r"C:\a", r"D:\b"]);
+        assert!(results.iter().all(|r| matches!(r, Err(Error::VolumeEnumeration { .. }))));
+        assert!(resolver.group_by_volume([r"C:\a"]).is_empty());
+
+        backend.clear_failures();
+        assert!(resolver.resolve_many([r"C:\a"])[0].is_ok());
+    }
+
+    #[test]
+    fn test_batch_refresh_on_miss() {
+        let backend = layout();
+        let resolver = Resolver::builder().backend(backend.clone()).refresh_policy(RefreshPolicy::OnMiss).build();
+        resolver.try_init().unwrap();
+
+        // 批内首次未命中时刷新，刷新后的映射表供其余路径复用
+        backend.mount("E", r"E:\");
+        backend.mount("G", r"G:\");
+        let results = resolver.resolve_many([r"C:\x", r"E:\x", r"F:\x", r"G:\x"]);
+        assert_eq!(results[0].as_ref().unwrap().as_str(), "C");
+        assert_eq!(results[1].as_ref().unwrap().as_str(), "E");
+        assert!(results[2].is_err());
+        assert_eq!(results[3].as_ref().unwrap().as_str(), "G");
+    }
+
+    #[test]
+    fn test_batch_unix() {
+        let resolver = Resolver::builder()
+            .backend(FakeBackend::unix().with_volume("8:1", ["/"]).with_volume("8:17", ["/home"]))
+            .build();
+        let groups = resolver.group_by_volume(["/etc/hosts", "/home", "/home/a/b", "/", "/etc/passwd"]);
+        assert_eq!(groups["8:1"].len(), 3);
+        assert_eq!(groups["8:17"], [PathBuf::from("/home"), PathBuf::from("/home/a/b")]);
+    }
+
+    #[test]
+    fn test_batch_snapshot() {
+        let backend = layout();
+        let resolver = Resolver::builder().backend(backend.clone()).build();
+        let mut batch = resolver.batch();
+
+        let resolution = batch.resolve(r"D:\Vdisks\Wechat\file").unwrap();
+        assert_eq!(resolution.volume().as_str(), "W");
+        assert_eq!(resolution.mount_point().as_os_str(), r"D:\Vdisks\Wechat\");
+        assert_eq!(batch.resolve(r"D:\Vdisks\file").unwrap().mount_point().as_os_str(), r"D:\");
+        assert!(matches!(batch.resolve(r"C:\missing"), Err(Error::NotFound { .. })));
+
+        // 批次内始终使用创建时的快照
+        backend.mount("E", r"E:\");
+        resolver.reinitialize().unwrap();
+        assert!(batch.resolve(r"E:\x").is_err());
+        assert_eq!(resolver.batch().resolve_volume(r"E:\x").unwrap().as_str(), "E");
+    }
+
+    #[test]
+    fn test_batch_link() {
+        let backend = FakeBackend::unix()
+            .with_volume("8:1", ["/"])
+            .with_volume("8:17", ["/mnt/data"])
+            .with_link("/home/link", "/mnt/data/target");
+        let resolver = Resolver::builder().backend(backend).build();
+
+        // 链接与父目录位于不同的卷，不能沿用父目录的挂载点
+        let paths = ["/home/a", "/home/link", "/home/link/file", "/home/b"];
+        let ids: Vec<String> = resolver.resolve_many(paths).into_iter().map(|r| r.unwrap().as_str().to_owned()).collect();
+        assert_eq!(ids, ["8:1", "8:17", "8:17", "8:1"]);
+        for (path, id) in paths.iter().zip(&ids) {
+            assert_eq!(resolver.resolve_volume(path).unwrap().as_str(), id);
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parallel() {
+        let resolver = Resolver::builder().backend(layout()).build();
+        let paths: Vec<String> = (0..1000).map(|i| format!(r"{}:\dir{}\file{}", ["C", "D"][i % 2], i % 7, i)).collect();
+
+        let results = resolver.par_resolve_many(&paths);
+        assert_eq!(results.len(), paths.len());
+        for (i, result) in results.iter().enumerate() {
+            assert_eq!(result.as_ref().unwrap().as_str(), ["C", "D"][i % 2]);
+        }
+
+        let groups = resolver.par_group_by_volume(&paths);
+        assert_eq!(groups["C"].len(), 500);
+        assert_eq!(groups["C"][1], PathBuf::from(&paths[2]));
+    }
+}
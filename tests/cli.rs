@@ -0,0 +1,115 @@
+#[cfg(all(test, feature = "cli"))]
+mod test {
+    use std::io::Write as _;
+    use std::process::{Command, Output, Stdio};
+
+    /// 运行 `samevol` 命令行工具
+    fn samevol(args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_samevol")).args(args).output().unwrap()
+    }
+
+    /// 运行 `samevol` 并从标准输入写入数据
+    fn samevol_stdin(args: &[&str], input: &[u8]) -> Output {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_samevol"))
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.take().unwrap().write_all(input).unwrap();
+        child.wait_with_output().unwrap()
+    }
+
+    fn stdout(output: &Output) -> String {
+        String::from_utf8(output.stdout.clone()).unwrap()
+    }
+
+    #[test]
+    fn test_check() {
+        let temp = std::env::temp_dir();
+        let temp = temp.to_str().unwrap();
+
+        let output = samevol(&["check", temp, temp]);
+        assert_eq!(output.status.code(), Some(0));
+        assert_eq!(stdout(&output).trim(), "same");
+
+        let output = samevol(&["check", "--json", temp, temp]);
+        assert_eq!(output.status.code(), Some(0));
+        let value: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+        assert_eq!(value["same"], true);
+        assert_eq!(value["paths"][0]["device_path"], value["paths"][1]["device_path"]);
+
+        // 参数错误同样以 2 退出
+        assert_eq!(samevol(&["check", temp]).status.code(), Some(2));
+        assert_eq!(samevol(&[]).status.code(), Some(2));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_check_different() {
+        let output = samevol(&["check", "/", "/proc"]);
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(stdout(&output).trim(), "different");
+    }
+
+    #[test]
+    fn test_which_and_list() {
+        let temp = std::env::temp_dir();
+        let device_path = samevol::try_resolve_device_path(&temp).unwrap();
+
+        let output = samevol(&["which", temp.to_str().unwrap()]);
+        assert!(output.status.success());
+        assert_eq!(stdout(&output).trim(), device_path);
+
+        let output = samevol(&["--json", "list"]);
+        assert!(output.status.success());
+        let value: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+        let volumes = value["volumes"].as_array().unwrap();
+        assert!(volumes.iter().any(|volume| volume["device_path"] == device_path.as_str()));
+
+        let output = samevol(&["list"]);
+        assert!(stdout(&output).lines().any(|line| line.split('\t').next() == Some(&device_path)));
+
+        let output = samevol(&["refresh", "--json"]);
+        let value: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+        assert_eq!(value["mappings"].as_u64(), Some(samevol::try_init().unwrap() as u64));
+    }
+
+    #[test]
+    fn test_batch() {
+        let temp = std::env::temp_dir();
+        let temp = temp.to_str().unwrap();
+        let device_path = samevol::try_resolve_device_path(temp).unwrap();
+
+        // 空行被忽略，每个路径输出一行 JSON
+        let output = samevol_stdin(&["batch"], format!("{0}\n\n{0}\r\n", temp).as_bytes());
+        assert!(output.status.success());
+        let lines: Vec<serde_json::Value> =
+            stdout(&output).lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        for line in &lines {
+            assert_eq!(line["path"], temp);
+            assert_eq!(line["device_path"], device_path.as_str());
+            assert!(line["mount_point"].is_string());
+            assert!(line["error"].is_null());
+        }
+
+        let output = samevol_stdin(&["batch", "-0", "--pairs"], format!("{0}\0{0}\0{0}", temp).as_bytes());
+        assert_eq!(output.status.code(), Some(2));
+        let lines: Vec<serde_json::Value> =
+            stdout(&output).lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["same"], true);
+        assert_eq!(lines[0]["paths"].as_array().unwrap().len(), 2);
+        // 多出的最后一个路径
+        assert!(lines[1]["same"].is_null());
+        assert!(lines[1]["error"].is_string());
+
+        // 以 NUL 分隔时保留文件名末尾的 `\r`
+        let name = format!("{}/name\r", temp);
+        let output = samevol_stdin(&["batch", "-0"], format!("{}\0", name).as_bytes());
+        assert!(output.status.success());
+        let line: serde_json::Value = serde_json::from_str(stdout(&output).trim_end_matches('\n')).unwrap();
+        assert_eq!(line["path"], name.as_str());
+    }
+}
@@ -0,0 +1,156 @@
+#[cfg(test)]
+mod test {
+    use std::ffi::OsString;
+    use std::io;
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use samevol::*;
+
+    fn layout() -> Arc<FakeBackend> {
+        Arc::new(
+            FakeBackend::windows()
+                .with_volume("C", [r"C:\"])
+                .with_volume("D", [r"D:\"])
+                .with_path_error(r"C:\missing", io::ErrorKind::NotFound)
+                .with_path_error(r"C:\secret", io::ErrorKind::PermissionDenied)
+                .with_path_error(r"C:\bad", io::ErrorKind::InvalidInput),
+        )
+    }
+
+    #[test]
+    fn test_path_errors() {
+        let resolver = Resolver::builder().backend(layout()).build();
+
+        let err = resolver.try_resolve_device_path(r"C:\missing").unwrap_err();
+        assert!(matches!(err, Error::NotFound { .. }));
+        assert_eq!(err.path(), Some(Path::new(r"C:\missing")));
+        assert_eq!(err.os_error().unwrap().kind(), io::ErrorKind::NotFound);
+
+        let err = resolver.try_resolve_device_path(r"C:\secret").unwrap_err();
+        assert!(matches!(err, Error::PermissionDenied { .. }));
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+
+        let err = resolver.try_resolve_device_path(r"C:\bad").unwrap_err();
+        assert!(matches!(err, Error::InvalidPath { .. }));
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_unknown_drive() {
+        let resolver = Resolver::builder().backend(layout()).build();
+
+        // 后端找不到挂载点时同样归类为路径错误
+        let err = resolver.try_resolve_device_path(r"F:\x").unwrap_err();
+        assert!(matches!(err, Error::NotFound { .. }));
+        assert_eq!(err.path(), Some(Path::new(r"F:\x")));
+    }
+
+    #[test]
+    fn test_mount_point_without_volume() {
+        let backend = layout();
+        let resolver = Resolver::builder().backend(backend.clone()).build();
+        assert_eq!(resolver.reinitialize().unwrap(), 2);
+
+        // 挂载后未刷新：后端能找到挂载点，但映射表中没有对应的卷
+        backend.mount("E", r"E:\");
+        backend.unmount(r"C:\");
+        let err = resolver.try_resolve_device_path(r"E:\x").unwrap_err();
+        match err {
+            Error::NoMountPoint { path, mount_point } => {
+                assert_eq!(path.as_os_str(), r"E:\x");
+                assert_eq!(mount_point, r"E:\");
+            }
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn test_enumeration_failure() {
+        let backend = layout();
+        backend.fail_enumeration(io::ErrorKind::PermissionDenied);
+        let resolver = Resolver::builder().backend(backend.clone()).build();
+
+        let err = resolver.try_resolve_device_path(r"C:\x").unwrap_err();
+        assert!(matches!(err, Error::VolumeEnumeration { .. }));
+        assert!(err.path().is_none());
+
+        // 未成功构建前每次调用都会重试
+        let err = resolver.try_resolve_device_path(r"C:\x").unwrap_err();
+        assert!(matches!(err, Error::VolumeEnumeration { .. }));
+
+        let err = resolver.reinitialize().unwrap_err();
+        assert!(matches!(err, Error::VolumeEnumeration { .. }));
+    }
+
+    #[test]
+    fn test_try_is_same_vol() {
+        let resolver = Resolver::builder().backend(layout()).build();
+        assert!(resolver.try_is_same_vol(r"C:\a", r"C:\b").unwrap());
+        assert!(!resolver.try_is_same_vol(r"C:\a", r"D:\b").unwrap());
+        assert!(resolver.try_is_same_vol(r"C:\a", r"C:\missing").is_err());
+        assert!(!resolver.is_same_vol(r"C:\a", r"C:\missing"));
+    }
+
+    /// 第一次构建映射表时 panic 的后端
+    #[derive(Default)]
+    struct PanickingBackend {
+        panicked: AtomicBool,
+    }
+
+    impl VolumeBackend for PanickingBackend {
+        fn volumes(&self) -> io::Result<Vec<(String, Vec<OsString>)>> {
+            if !self.panicked.swap(true, Ordering::SeqCst) {
+                panic!("backend failure");
+            }
+            Ok(vec![("8:1".to_owned(), vec!["/".into()])])
+        }
+
+        fn full_path(&self, path: &Path) -> io::Result<PathBuf> {
+            Ok(path.to_path_buf())
+        }
+
+        fn volume_mount_point(&self, _full_path: &Path) -> io::Result<OsString> {
+            Ok("/".into())
+        }
+    }
+
+    #[test]
+    fn test_recovers_from_panic() {
+        let resolver = Arc::new(Resolver::builder().backend(PanickingBackend::default()).build());
+
+        let cloned = resolver.clone();
+        assert!(std::thread::spawn(move || cloned.resolve_device_path("/x")).join().is_err());
+
+        // panic 不会使解析器永久不可用
+        assert!(matches!(resolver.init_status(), InitStatus::Uninitialized));
+        assert_eq!(resolver.try_resolve_device_path("/x").unwrap(), "8:1");
+    }
+
+    #[test]
+    fn test_into_io_error() {
+        let err = Error::NoMountPoint { path: "x".into(), mount_point: "y".into() };
+        let cloned = err.clone();
+        let io_err = io::Error::from(err);
+        assert_eq!(io_err.kind(), io::ErrorKind::NotFound);
+        assert_eq!(io_err.to_string(), cloned.to_string());
+    }
+
+    #[test]
+    fn test_metadata_unsupported() {
+        let resolver = Resolver::builder().backend(PanickingBackend { panicked: AtomicBool::new(true) }).build();
+
+        // 后端未实现元数据查询时返回 `Unsupported`
+        let err = resolver.volume_metadata("/x").unwrap_err();
+        assert!(matches!(&err, Error::Metadata { volume, .. } if volume.as_str() == "8:1"));
+        assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+        assert!(err.os_error().is_some());
+        assert!(err.path().is_none());
+        assert_eq!(err.clone().to_string(), err.to_string());
+
+        // 路径无法解析时返回路径错误
+        let resolver = Resolver::builder().backend(layout()).build();
+        assert!(matches!(resolver.volume_metadata(r"C:\missing"), Err(Error::NotFound { .. })));
+    }
+}
@@ -0,0 +1,192 @@
+#[cfg(test)]
+mod test {
+    use std::ffi::OsString;
+    use std::io;
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use samevol::*;
+
+    const VOL_D: &str = r"\\?\Volume{11111111-1111-1111-1111-111111111111}\";
+    const VOL_VHD: &str = r"\\?\Volume{22222222-2222-2222-2222-222222222222}\";
+
+    fn full_path(backend: &FakeBackend, path: &str) -> io::Result<OsString> {
+        backend.full_path(Path::new(path)).map(|p| p.into_os_string())
+    }
+
+    fn mount_point(backend: &FakeBackend, path: &str) -> io::Result<OsString> {
+        backend.volume_mount_point(Path::new(path))
+    }
+
+    fn windows_layout() -> FakeBackend {
+        FakeBackend::windows()
+            .with_volume(VOL_D, [r"D:\"])
+            .with_volume(VOL_VHD, [r"D:\Vdisks\Wechat"])
+            .with_current_dir(r"D:\Projects")
+    }
+
+    #[test]
+    fn test_full_path() {
+        let backend = windows_layout();
+        assert_eq!(full_path(&backend, "src").unwrap(), r"D:\Projects\src");
+        assert_eq!(full_path(&backend, r"..\Vdisks\.\Wechat\").unwrap(), r"D:\Vdisks\Wechat\");
+        assert_eq!(full_path(&backend, "D:/a/b/../c").unwrap(), r"D:\a\c");
+        assert_eq!(full_path(&backend, r"D:sub").unwrap(), r"D:\Projects\sub");
+        assert_eq!(full_path(&backend, r"E:sub").unwrap(), r"E:\sub");
+        assert_eq!(full_path(&backend, r"\rooted").unwrap(), r"D:\rooted");
+        assert_eq!(full_path(&backend, r"\\server\share\..\x").unwrap(), r"\\server\share\x");
+        assert!(full_path(&backend, "").is_err());
+    }
+
+    #[test]
+    fn test_volume_mount_point() {
+        let backend = windows_layout();
+        assert_eq!(mount_point(&backend, r"D:\Vdisks\Wechat\a.txt").unwrap(), r"D:\Vdisks\Wechat\");
+        assert_eq!(mount_point(&backend, r"D:\Vdisks\Wechat").unwrap(), r"D:\Vdisks\Wechat\");
+        // 挂载点匹配需以路径分量为边界
+        assert_eq!(mount_point(&backend, r"D:\Vdisks\WechatOld").unwrap(), r"D:\");
+
+        let err = mount_point(&backend, r"E:\file").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn test_unix_layout() {
+        let backend = FakeBackend::unix()
+            .with_volume("8:1", ["/"])
+            .with_volume("8:17", ["/home", "/srv/home"])
+            .with_current_dir("/home/user");
+
+        assert_eq!(full_path(&backend, "../other/./f").unwrap(), "/home/other/f");
+        assert_eq!(mount_point(&backend, "/srv/home/x").unwrap(), "/srv/home/");
+        assert_eq!(mount_point(&backend, "/srv/homework").unwrap(), "/");
+
+        let volumes = backend.volumes().unwrap();
+        assert_eq!(volumes[1], ("8:17".to_owned(), vec!["/home/".into(), "/srv/home/".into()]));
+    }
+
+    #[test]
+    fn test_mount_and_unmount() {
+        let backend = FakeBackend::windows().with_volume(VOL_D, [r"D:\"]);
+
+        backend.mount(VOL_VHD, r"D:\mnt");
+        assert_eq!(mount_point(&backend, r"D:\mnt\x").unwrap(), r"D:\mnt\");
+
+        // 在同一位置重新挂载会替换原有卷
+        backend.mount(VOL_D, r"D:\mnt");
+        let volumes = backend.volumes().unwrap();
+        assert_eq!(volumes[1], (VOL_VHD.to_owned(), vec![]));
+
+        assert!(backend.unmount(r"D:\mnt\"));
+        assert!(!backend.unmount(r"D:\mnt"));
+        assert!(backend.remove_volume(VOL_VHD));
+        assert_eq!(backend.volumes().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_remove_volume_canonical() {
+        let lowercase = VOL_VHD.to_lowercase();
+        let backend = FakeBackend::windows()
+            .with_volume(&lowercase, [r"E:\"])
+            .with_metadata(&lowercase, VolumeMetadata::new().with_label("VHD"))
+            .with_block_device(&lowercase, "disk1")
+            .with_physical_disks(&lowercase, ["PhysicalDrive1"]);
+
+        // 设备路径按规范形式比较，且移除卷的全部附加信息
+        assert!(backend.remove_volume(VOL_VHD));
+        assert!(backend.volumes().unwrap().is_empty());
+        assert!(!backend.remove_volume(&lowercase));
+
+        let backend = Arc::new(backend.with_volume(VOL_VHD, [r"E:\"]));
+        let resolver = Resolver::builder().backend(backend.clone()).build();
+        let volume = resolver.volumes().unwrap().get(VOL_VHD).unwrap().clone();
+        assert!(backend.volume_metadata(&volume).is_err());
+        assert_eq!(backend.block_device(&volume).unwrap(), volume.id().as_str());
+        assert!(backend.physical_disks(&volume).is_err());
+    }
+
+    #[test]
+    fn test_injected_failures() {
+        let backend = windows_layout().with_path_error(r"D:\secret", io::ErrorKind::PermissionDenied);
+
+        let err = full_path(&backend, r"..\secret").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+
+        backend.fail_enumeration(io::ErrorKind::Other);
+        assert!(backend.volumes().is_err());
+
+        backend.clear_failures();
+        assert!(backend.volumes().is_ok());
+        assert!(full_path(&backend, r"..\secret").is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_unicode_mount_point() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt as _;
+
+        let mnt = OsStr::from_bytes(b"/mnt/\xff");
+        let backend = Arc::new(FakeBackend::unix().with_volume("8:1", ["/"]).with_volume("8:2", [mnt]));
+        assert_eq!(backend.volume_mount_point(&Path::new(mnt).join("f")).unwrap().as_bytes(), b"/mnt/\xff/");
+
+        let resolver = Resolver::builder().backend(backend).build();
+        assert_eq!(resolver.resolve_device_path(Path::new(mnt).join("f")).as_deref(), Some("8:2"));
+        assert_eq!(resolver.resolve_device_path(OsStr::from_bytes(b"/mnt/\xfe")).as_deref(), Some("8:1"));
+    }
+
+    // 全局后端为进程共享状态，相关断言集中在同一个测试中
+    #[test]
+    fn test_global_backend() {
+        let backend = Arc::new(windows_layout().with_path_error(r"D:\denied", io::ErrorKind::PermissionDenied));
+        assert_eq!(set_backend(backend.clone()).unwrap(), 2);
+
+        assert!(is_same_vol(r"D:\", r"D:\Vdisks\another_file.txt"));
+        assert!(!is_same_vol(r"D:\", r"D:\Vdisks\Wechat\another_file.txt"));
+        assert_eq!(resolve_device_path(r"..\Vdisks\Wechat").as_deref(), Some(VOL_VHD));
+        assert_eq!(resolve_device_path(r"D:\denied"), None);
+
+        // 挂载变化在刷新映射表后生效
+        backend.mount(VOL_VHD, r"D:\Projects");
+        assert_eq!(resolve_device_path("src").as_deref(), Some(VOL_D));
+        assert_eq!(reinitialize_volume_map().unwrap(), 3);
+        assert_eq!(resolve_device_path("src").as_deref(), Some(VOL_VHD));
+
+        // 刷新失败时保留原有映射表
+        backend.fail_enumeration(io::ErrorKind::Other);
+        assert!(reinitialize_volume_map().is_err());
+        assert_eq!(resolve_device_path("src").as_deref(), Some(VOL_VHD));
+    }
+
+    #[test]
+    fn test_volume_metadata() {
+        let backend = Arc::new(
+            windows_layout()
+                .with_metadata(VOL_D, VolumeMetadata::new().with_fs_type("NTFS").with_label("Data").with_capacity(100, 40, 30))
+                .with_volume(r"\\?\Volume{33333333-3333-3333-3333-333333333333}\", [r"E:\"]),
+        );
+        let resolver = Resolver::builder().backend(backend.clone()).build();
+
+        let metadata = resolver.volume_metadata(r"D:\Projects\file").unwrap();
+        assert_eq!(metadata.fs_type(), Some("NTFS"));
+        assert_eq!(metadata.label(), Some("Data"));
+        assert_eq!(metadata.serial_number(), None);
+        assert_eq!((metadata.total_bytes(), metadata.free_bytes(), metadata.available_bytes()), (Some(100), Some(40), Some(30)));
+
+        // 元数据按卷查询，每次调用都反映后端的当前状态
+        backend.set_metadata(&VOL_D.to_uppercase(), VolumeMetadata::new().with_capacity(100, 10, 5));
+        let volumes = resolver.volumes().unwrap();
+        let metadata = resolver.query_metadata(volumes.get(VOL_D).unwrap()).unwrap();
+        assert_eq!(metadata.available_bytes(), Some(5));
+        assert_eq!(metadata.fs_type(), None);
+
+        // 未声明元数据的卷
+        let err = resolver.volume_metadata(r"E:\x").unwrap_err();
+        assert!(matches!(&err, Error::Metadata { volume, .. } if volume.as_str().contains("33333333")));
+        assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+        assert!(resolver.volume_metadata(r"D:\Vdisks\Wechat\x").is_err());
+
+        backend.remove_volume(VOL_D);
+        assert!(resolver.query_metadata(volumes.get(VOL_D).unwrap()).is_err());
+    }
+}
@@ -0,0 +1,259 @@
+#[cfg(test)]
+mod test {
+    use std::fs;
+    use std::io;
+    #[cfg(unix)]
+    use std::path::Path;
+    use std::path::PathBuf;
+
+    use samevol::fs::*;
+    #[cfg(unix)]
+    use samevol::{FakeBackend, Resolver};
+
+    /// 每个测试独立的临时目录，结束时删除
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("samevol-fs-{}-{}", std::process::id(), name));
+            let _ = fs::remove_dir_all(&path);
+            fs::create_dir_all(&path).unwrap();
+            TempDir(path)
+        }
+
+        fn join(&self, path: &str) -> PathBuf {
+            self.0.join(path)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// 将 `dst_root` 声明为独立卷的解析器，使移动到其中的路径必须复制
+    #[cfg(unix)]
+    fn cross_volume_resolver(dst_root: &Path) -> Resolver {
+        Resolver::builder()
+            .backend(FakeBackend::unix().with_volume("8:1", ["/"]).with_volume("8:17", [dst_root]))
+            .build()
+    }
+
+    #[test]
+    fn test_rename_same_volume() {
+        let dir = TempDir::new("rename");
+        fs::write(dir.join("a.txt"), b"hello").unwrap();
+
+        assert_eq!(move_path(dir.join("a.txt"), dir.join("b.txt")).unwrap(), MoveMethod::Renamed);
+        assert!(!dir.join("a.txt").exists());
+        assert_eq!(fs::read(dir.join("b.txt")).unwrap(), b"hello");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_rename_symlink_across_devices() {
+        let dir = TempDir::new("rename-symlink");
+        // 链接目标位于另一个文件系统（procfs），链接本身仍可重命名
+        std::os::unix::fs::symlink("/proc/self/status", dir.join("link")).unwrap();
+
+        assert_eq!(move_path(dir.join("link"), dir.join("moved")).unwrap(), MoveMethod::Renamed);
+        assert!(fs::symlink_metadata(dir.join("link")).is_err());
+        assert_eq!(fs::read_link(dir.join("moved")).unwrap(), Path::new("/proc/self/status"));
+    }
+
+    #[test]
+    fn test_destination_exists() {
+        let dir = TempDir::new("exists");
+        fs::write(dir.join("a.txt"), b"a").unwrap();
+        fs::write(dir.join("b.txt"), b"b").unwrap();
+
+        let err = move_path(dir.join("a.txt"), dir.join("b.txt")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read(dir.join("a.txt")).unwrap(), b"a");
+        assert_eq!(fs::read(dir.join("b.txt")).unwrap(), b"b");
+
+        let err = move_path(dir.join("missing"), dir.join("c.txt")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_copy_tree() {
+        use std::os::unix::fs::PermissionsExt as _;
+        use std::time::{Duration, SystemTime};
+
+        let dir = TempDir::new("copy");
+        let src = dir.join("src");
+        fs::create_dir_all(src.join("nested/deeper")).unwrap();
+        fs::write(src.join("a.txt"), b"alpha").unwrap();
+        fs::write(src.join("nested/b.bin"), vec![7u8; 600 * 1024]).unwrap();
+        fs::write(src.join("nested/deeper/c.txt"), b"").unwrap();
+        std::os::unix::fs::symlink("nested/b.bin", src.join("link")).unwrap();
+
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+        fs::File::options().write(true).open(src.join("a.txt")).unwrap().set_modified(modified).unwrap();
+        fs::set_permissions(src.join("a.txt"), fs::Permissions::from_mode(0o440)).unwrap();
+        fs::set_permissions(src.join("nested/deeper"), fs::Permissions::from_mode(0o550)).unwrap();
+
+        let resolver = cross_volume_resolver(&dir.join("volume"));
+        fs::create_dir(dir.join("volume")).unwrap();
+        let dst = dir.join("volume/dst");
+
+        let mut reports = Vec::new();
+        let method = MoveOptions::new()
+            .resolver(&resolver)
+            .progress(|progress| {
+                reports.push((progress.bytes_copied(), progress.total_bytes(), progress.files_copied(), progress.total_files()))
+            })
+            .move_path(&src, &dst)
+            .unwrap();
+        assert_eq!(method, MoveMethod::Copied);
+        assert!(!src.exists());
+
+        assert_eq!(fs::read(dst.join("a.txt")).unwrap(), b"alpha");
+        assert_eq!(fs::read(dst.join("nested/b.bin")).unwrap().len(), 600 * 1024);
+        assert_eq!(fs::read(dst.join("nested/deeper/c.txt")).unwrap(), b"");
+        assert_eq!(fs::read_link(dst.join("link")).unwrap(), Path::new("nested/b.bin"));
+
+        let metadata = fs::metadata(dst.join("a.txt")).unwrap();
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o440);
+        assert_eq!(metadata.modified().unwrap(), modified);
+        assert_eq!(fs::metadata(dst.join("nested/deeper")).unwrap().permissions().mode() & 0o777, 0o550);
+
+        // 进度单调递增，最终覆盖全部文件和字节
+        assert!(reports.windows(2).all(|w| w[0].0 <= w[1].0 && w[0].2 <= w[1].2));
+        assert_eq!(*reports.last().unwrap(), (600 * 1024 + 5, 600 * 1024 + 5, 4, 4));
+
+        fs::set_permissions(dst.join("nested/deeper"), fs::Permissions::from_mode(0o750)).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_copy_file() {
+        let dir = TempDir::new("copy-file");
+        fs::create_dir(dir.join("volume")).unwrap();
+        fs::write(dir.join("a.txt"), b"alpha").unwrap();
+
+        let resolver = cross_volume_resolver(&dir.join("volume"));
+        let method = MoveOptions::new().resolver(&resolver).move_path(dir.join("a.txt"), dir.join("volume/a.txt")).unwrap();
+        assert_eq!(method, MoveMethod::Copied);
+        assert!(!dir.join("a.txt").exists());
+        assert_eq!(fs::read(dir.join("volume/a.txt")).unwrap(), b"alpha");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rollback() {
+        let dir = TempDir::new("rollback");
+        let src = dir.join("src");
+        fs::create_dir_all(src.join("sub")).unwrap();
+        fs::write(src.join("a.txt"), b"alpha").unwrap();
+        fs::write(src.join("sub/b.txt"), b"beta").unwrap();
+        // 套接字等特殊文件无法复制
+        let _socket = std::os::unix::net::UnixListener::bind(src.join("sub/socket")).unwrap();
+
+        fs::create_dir(dir.join("volume")).unwrap();
+        let resolver = cross_volume_resolver(&dir.join("volume"));
+        let err = MoveOptions::new().resolver(&resolver).move_path(&src, dir.join("volume/dst")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+
+        // 目标被完整回滚，源路径保持不变
+        assert!(!dir.join("volume/dst").exists());
+        assert_eq!(fs::read(src.join("a.txt")).unwrap(), b"alpha");
+        assert_eq!(fs::read(src.join("sub/b.txt")).unwrap(), b"beta");
+    }
+
+    #[test]
+    fn test_atomic_file() {
+        use std::io::Write as _;
+
+        let dir = TempDir::new("atomic");
+        fs::write(dir.join("a.txt"), b"old").unwrap();
+
+        let mut file = atomic_file(dir.join("a.txt")).unwrap();
+        assert_eq!(file.path().parent(), Some(dir.0.as_path()));
+        assert_eq!(file.destination(), dir.join("a.txt"));
+        file.write_all(b"new").unwrap();
+        // 提交前目标保持不变
+        assert_eq!(fs::read(dir.join("a.txt")).unwrap(), b"old");
+        let temp = file.path().to_path_buf();
+        file.commit().unwrap();
+        assert_eq!(fs::read(dir.join("a.txt")).unwrap(), b"new");
+        assert!(!temp.exists());
+
+        // 未提交即丢弃
+        let mut file = atomic_file(dir.join("a.txt")).unwrap();
+        file.write_all(b"discarded").unwrap();
+        let temp = file.path().to_path_buf();
+        drop(file);
+        assert!(!temp.exists());
+        assert_eq!(fs::read(dir.join("a.txt")).unwrap(), b"new");
+
+        let file = atomic_file(dir.join("b.txt")).unwrap();
+        let temp = file.path().to_path_buf();
+        file.discard().unwrap();
+        assert!(!temp.exists());
+        assert!(!dir.join("b.txt").exists());
+
+        assert_eq!(atomic_file("/").unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_atomic_file_fallback() {
+        use std::io::Write as _;
+
+        let dir = TempDir::new("atomic-fallback");
+        fs::create_dir(dir.join("volume")).unwrap();
+        let resolver = cross_volume_resolver(&dir.join("volume"));
+
+        // 缓存目录默认不启用
+        let dst = dir.join("volume/missing/a.txt");
+        let err = AtomicFileOptions::new().resolver(&resolver).create(&dst).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert!(!dir.join("volume/.samevol-tmp").exists());
+
+        // 目标目录不存在时使用目标所在卷的缓存目录
+        let file = AtomicFileOptions::new().resolver(&resolver).volume_cache_dir(".samevol-tmp").create(&dst).unwrap();
+        assert_eq!(file.path().parent(), Some(dir.join("volume/.samevol-tmp").as_path()));
+        drop(file);
+
+        // 后备目录与目标不在同一卷时不会使用
+        let options = AtomicFileOptions::new()
+            .resolver(&resolver)
+            .volume_cache_dir(".samevol-tmp")
+            .no_volume_cache_dir()
+            .fallback_dir(dir.join("elsewhere"))
+            .fallback_dir(dir.join("volume/fallback"));
+        fs::create_dir(dir.join("elsewhere")).unwrap();
+        fs::create_dir(dir.join("volume/fallback")).unwrap();
+        let mut file = options.create(&dst).unwrap();
+        assert_eq!(file.path().parent(), Some(dir.join("volume/fallback").as_path()));
+        file.write_all(b"data").unwrap();
+        fs::create_dir(dir.join("volume/missing")).unwrap();
+        file.commit().unwrap();
+        assert_eq!(fs::read(&dst).unwrap(), b"data");
+
+        let options = AtomicFileOptions::new()
+            .resolver(&resolver)
+            .fallback_dir(dir.join("elsewhere"));
+        let err = options.create(dir.join("volume/missing/b/c.txt")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::CrossesDevices);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_atomic_file_mount_point_destination() {
+        let dir = TempDir::new("atomic-mount-point");
+        let dst = dir.join("mounted.txt");
+        fs::write(&dst, b"old").unwrap();
+        // 目标本身是挂载点（如绑定挂载的文件），其所在目录位于另一个挂载上
+        let resolver = cross_volume_resolver(&dst);
+
+        let err = AtomicFileOptions::new().resolver(&resolver).create(&dst).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::CrossesDevices);
+        assert_eq!(fs::read_dir(dir.join("")).unwrap().count(), 1);
+        assert_eq!(fs::read(&dst).unwrap(), b"old");
+    }
+}
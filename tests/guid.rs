@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod test {
+    use samevol::guid::*;
+
+    const CANONICAL: &str = r"\\?\Volume{e8a7f3c2-1234-5678-9abc-def012345678}\";
+
+    #[test]
+    fn test_parse_volume_guid_path() {
+        for input in [
+            CANONICAL,
+            r"\\?\Volume{e8a7f3c2-1234-5678-9abc-def012345678}",
+            r"\\?\volume{E8A7F3C2-1234-5678-9ABC-DEF012345678}\",
+            r"\\?\VOLUME{e8a7F3c2-1234-5678-9AbC-def012345678}",
+        ] {
+            let path = VolumeGuidPath::parse(input).unwrap();
+            assert_eq!(path.to_string(), CANONICAL);
+            assert_eq!(path.guid().to_string(), "e8a7f3c2-1234-5678-9abc-def012345678");
+        }
+    }
+
+    #[test]
+    fn test_reject_malformed_volume_guid_path() {
+        for input in [
+            "",
+            r"\\?\Volume{}",
+            r"\\?\Volume{e8a7f3c2-1234-5678-9abc-def012345678}\\",
+            r"\\?\Volume{e8a7f3c2-1234-5678-9abc-def012345678",
+            r"\\?\Volume{e8a7f3c2-1234-5678-9abc-def01234567}\",
+            r"\\?\Volume{e8a7f3c2-1234-5678-9abc-def0123456789}\",
+            r"\\?\Volume{e8a7f3c2-1234-5678-9abcdef0-12345678}\",
+            r"\\?\Volume{e8a7f3c2-1234-5678-9abc-def01234567g}\",
+            r"\\?\Volume{+8a7f3c2-1234-5678-9abc-def012345678}\",
+            r"\\.\Volume{e8a7f3c2-1234-5678-9abc-def012345678}\",
+            r"Volume{e8a7f3c2-1234-5678-9abc-def012345678}\",
+            r"\\?\Volume{e8a7f3c2-1234-5678-9abc-def01234567８}\",
+        ] {
+            assert!(VolumeGuidPath::parse(input).is_err(), "{input}");
+        }
+
+        assert!(VolumeGuidPath::has_prefix(r"\\?\volume{oops"));
+        assert!(!VolumeGuidPath::has_prefix(r"\\?\Harddisk0"));
+    }
+
+    #[test]
+    fn test_parse_guid() {
+        let guid: Guid = "{E8A7F3C2-1234-5678-9ABC-DEF012345678}".parse().unwrap();
+        assert_eq!(guid, Guid::parse("e8a7f3c2-1234-5678-9abc-def012345678").unwrap());
+        assert_eq!(guid.as_fields(), (0xe8a7f3c2, 0x1234, 0x5678, &[0x9a, 0xbc, 0xde, 0xf0, 0x12, 0x34, 0x56, 0x78]));
+        assert_eq!(VolumeGuidPath::from(guid).to_string(), CANONICAL);
+
+        assert!(Guid::parse("{e8a7f3c2-1234-5678-9abc-def012345678").is_err());
+        assert!(Guid::parse("e8a7f3c2123456789abcdef012345678").is_err());
+    }
+
+    #[test]
+    fn test_gpt_bytes() {
+        // EFI 系统分区类型 GUID 及其在 GPT 分区表中的字节序
+        let guid: Guid = "c12a7328-f81f-11d2-ba4b-00a0c93ec93b".parse().unwrap();
+        let bytes = [
+            0x28, 0x73, 0x2a, 0xc1, 0x1f, 0xf8, 0xd2, 0x11, 0xba, 0x4b, 0x00, 0xa0, 0xc9, 0x3e, 0xc9, 0x3b,
+        ];
+        assert_eq!(guid.to_gpt_bytes(), bytes);
+        assert_eq!(Guid::from_gpt_bytes(bytes), guid);
+        assert_eq!(Guid::from_fields(0xc12a7328, 0xf81f, 0x11d2, [0xba, 0x4b, 0x00, 0xa0, 0xc9, 0x3e, 0xc9, 0x3b]), guid);
+    }
+
+    #[test]
+    fn test_device_path_volume_guid() {
+        let path: samevol::DevicePath = r"\\?\volume{E8A7F3C2-1234-5678-9ABC-DEF012345678}".parse().unwrap();
+        assert_eq!(path.as_str(), CANONICAL);
+        assert_eq!(path.volume_guid().unwrap().to_gpt_bytes()[..4], [0xc2, 0xf3, 0xa7, 0xe8]);
+        assert_eq!("8:1".parse::<samevol::DevicePath>().unwrap().volume_guid(), None);
+    }
+}
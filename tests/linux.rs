@@ -0,0 +1,102 @@
+#[cfg(all(test, target_os = "linux"))]
+mod test {
+    use samevol::*;
+
+    #[test]
+    fn test_basic() {
+        let path1 = "/proc/self";
+        let path2 = "/proc/cpuinfo";
+        assert!(is_same_vol(path1, path2));
+    }
+
+    #[test]
+    fn test_procfs_detection() {
+        let path1 = "/";
+        let path2 = "/proc/cpuinfo"; // procfs 总是独立挂载
+        assert!(!is_same_vol(path1, path2));
+    }
+
+    #[test]
+    fn test_resolve_device_path_of_relative() {
+        let resolved_path1 = resolve_device_path("src").unwrap();
+
+        let current_dir = std::env::current_dir().unwrap();
+        let current_dir_str = current_dir.to_str().unwrap();
+        let resolved_path2 = resolve_device_path(current_dir_str).unwrap();
+
+        assert_eq!(resolved_path1, resolved_path2);
+    }
+
+    #[test]
+    fn test_resolve_device_path_of_nonexistent() {
+        let resolved_path1 = resolve_device_path("/proc/no/such/file").unwrap();
+        let resolved_path2 = resolve_device_path("/proc").unwrap();
+        assert_eq!(resolved_path1, resolved_path2);
+    }
+
+    #[test]
+    fn test_reinitialize_volume_map() {
+        assert!(reinitialize_volume_map().unwrap() > 0);
+    }
+
+    #[test]
+    fn test_non_utf8_path() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt as _;
+
+        let temp_dir = std::env::temp_dir();
+        let path = temp_dir.join(OsStr::from_bytes(b"samevol-\xff-test"));
+        assert_eq!(try_resolve_device_path(&path).unwrap(), try_resolve_device_path(&temp_dir).unwrap());
+    }
+
+    #[test]
+    fn test_volume_metadata() {
+        let metadata = volume_metadata("/proc/cpuinfo").unwrap();
+        assert_eq!(metadata.fs_type(), Some("proc"));
+        assert_eq!(metadata.label(), None);
+
+        let metadata = volume_metadata(std::env::temp_dir()).unwrap();
+        assert!(metadata.fs_type().is_some());
+        let (total, free, available) =
+            (metadata.total_bytes().unwrap(), metadata.free_bytes().unwrap(), metadata.available_bytes().unwrap());
+        assert!(total > 0 && free <= total && available <= free);
+
+        for volume in &volumes().unwrap() {
+            if !volume.mount_points().is_empty() {
+                // 部分伪文件系统可能拒绝访问，但不应出现“未挂载”
+                if let Err(e) = query_metadata(volume) {
+                    assert_ne!(e.kind(), std::io::ErrorKind::NotFound, "{}", e);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_can_rename() {
+        let temp_dir = std::env::temp_dir();
+        assert!(can_rename(temp_dir.join("a"), temp_dir.join("b")));
+        assert!(!can_rename("/proc/self/status", temp_dir.join("status")));
+    }
+
+    #[test]
+    fn test_mount_id() {
+        let backend = LinuxBackend;
+        let temp_dir = std::env::temp_dir().canonicalize().unwrap();
+
+        // 不存在的路径取最近的已存在祖先所在的挂载
+        let mount_id = backend.mount_id(&temp_dir).unwrap().unwrap();
+        assert_eq!(backend.mount_id(&temp_dir.join("missing/file")).unwrap(), Some(mount_id));
+        assert_ne!(backend.mount_id("/proc/self".as_ref()).unwrap(), Some(mount_id));
+    }
+
+    #[test]
+    fn test_compare() {
+        let temp_dir = std::env::temp_dir();
+        for granularity in [Granularity::Mount, Granularity::Filesystem, Granularity::BlockDevice] {
+            assert!(compare(temp_dir.join("a"), temp_dir.join("b"), granularity));
+            assert!(!compare("/proc/self", &temp_dir, granularity));
+        }
+        // procfs 不在任何物理磁盘上
+        assert!(!try_compare("/proc/self", &temp_dir, Granularity::PhysicalDisk).unwrap());
+    }
+}
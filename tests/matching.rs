@@ -0,0 +1,148 @@
+#[cfg(test)]
+mod test {
+    use samevol::matching::*;
+    use samevol::{FakeBackend, Resolver};
+
+    #[test]
+    fn test_windows_key() {
+        let matcher = PathMatcher::windows();
+        assert_eq!(matcher.key(r"c:/mnt//vhd"), br"C:\MNT\VHD\");
+        assert_eq!(matcher.key(r"\\?\C:\mnt\"), br"C:\MNT\");
+        assert_eq!(matcher.key(r"\\.\C:\mnt"), br"C:\MNT\");
+        assert_eq!(matcher.key(r"\\?\UNC\server\share"), br"\\SERVER\SHARE\");
+        assert_eq!(matcher.key(r"//server//share"), br"\\SERVER\SHARE\");
+        assert_eq!(
+            matcher.key(r"\\?\Volume{e8a7f3c2-1234-5678-9abc-def012345678}\"),
+            br"\\?\VOLUME{E8A7F3C2-1234-5678-9ABC-DEF012345678}\"
+        );
+    }
+
+    #[test]
+    fn test_ntfs_case_folding() {
+        let matcher = PathMatcher::windows();
+        assert_eq!(matcher.key(r"C:\Über\ärger"), matcher.key(r"c:\üBER\ÄRGER"));
+        assert_eq!(matcher.key(r"C:\Σίσυφος"), matcher.key(r"C:\ΣΊΣΥΦΟΣ"));
+        // 大写形式不是单个字符时保持不变
+        assert_ne!(matcher.key(r"C:\straße"), matcher.key(r"C:\STRASSE"));
+        // BMP 之外的字符不转换
+        assert_ne!(matcher.key("C:\\\u{10428}"), matcher.key("C:\\\u{10400}"));
+    }
+
+    #[test]
+    fn test_case_folding_options() {
+        let exact = PathMatcher::windows().with_case_folding(CaseFolding::Exact);
+        assert_eq!(exact.case_folding(), CaseFolding::Exact);
+        assert!(!exact.contains(r"C:\Mnt", r"C:\mnt\x"));
+
+        let ascii = PathMatcher::unix().with_case_folding(CaseFolding::Ascii);
+        assert!(ascii.contains("/Mnt", "/mnt/x"));
+        assert!(!ascii.contains("/Äpfel", "/äpfel/x"));
+    }
+
+    #[test]
+    fn test_unix_key() {
+        let matcher = PathMatcher::unix();
+        assert_eq!(matcher.key("/mnt//Data"), b"/mnt/Data/");
+        assert_eq!(matcher.key("/"), b"/");
+        assert_eq!(matcher.key(r"/mnt\x"), br"/mnt\x/");
+        assert!(!matcher.contains("/mnt/data", "/mnt/Data/x"));
+    }
+
+    #[test]
+    fn test_component_boundary() {
+        for matcher in [PathMatcher::windows(), PathMatcher::unix()] {
+            assert!(matcher.contains("/foo", "/foo"));
+            assert!(matcher.contains("/foo/", "/foo/bar"));
+            assert!(!matcher.contains("/foo", "/foobar"));
+            assert!(!matcher.contains("/foo/", "/foobar/"));
+        }
+    }
+
+    #[test]
+    fn test_longest_match() {
+        let matcher = PathMatcher::windows();
+        let mount_points = [r"C:\", r"C:\mnt\vhd\", r"C:\mnt\"];
+        assert_eq!(matcher.longest_match(r"C:\MNT\VHD\x", mount_points), Some(r"C:\mnt\vhd\"));
+        assert_eq!(matcher.longest_match(r"C:\mnt\vhd2", mount_points), Some(r"C:\mnt\"));
+        assert_eq!(matcher.longest_match(r"D:\x", mount_points), None);
+    }
+
+    #[test]
+    fn test_unpaired_surrogate() {
+        // WTF-8 编码的未配对代理项原样保留
+        let matcher = PathMatcher::windows();
+        let path = unsafe { std::ffi::OsStr::from_encoded_bytes_unchecked(b"C:\\a\xed\xa0\x80b") };
+        assert_eq!(matcher.key(path), b"C:\\A\xed\xa0\x80B\\");
+    }
+
+    #[test]
+    fn test_resolver_case_insensitive() {
+        let resolver = Resolver::builder()
+            .backend(FakeBackend::windows().with_volume("A", [r"C:\"]).with_volume("B", [r"C:\Mnt\VHD"]))
+            .build();
+        assert_eq!(resolver.path_matcher(), PathMatcher::windows());
+
+        assert_eq!(resolver.resolve_device_path(r"c:\mnt\vhd\file").as_deref(), Some("B"));
+        assert_eq!(resolver.resolve_device_path(r"\\?\C:\MNT\VHD\file").as_deref(), Some("B"));
+        assert_eq!(resolver.resolve_device_path(r"C:\Mnt\VHDX\file").as_deref(), Some("A"));
+        assert!(resolver.is_same_vol(r"C:\MNT\VHD", r"c:\mnt\vhd\a\b"));
+    }
+
+    #[test]
+    fn test_resolver_matcher_override() {
+        let resolver = Resolver::builder()
+            .backend(FakeBackend::unix().with_volume("8:1", ["/"]))
+            .path_matcher(PathMatcher::unix().with_case_folding(CaseFolding::Ascii))
+            .build();
+        assert_eq!(resolver.path_matcher().case_folding(), CaseFolding::Ascii);
+        assert_eq!(resolver.resolve_device_path("/home").as_deref(), Some("8:1"));
+    }
+
+    #[test]
+    fn test_mount_index() {
+        let mut index = MountIndex::new(PathMatcher::windows());
+        assert!(index.is_empty());
+        assert_eq!(index.insert(r"C:\", 1), None);
+        assert_eq!(index.insert(r"C:\Mnt\Über", 2), None);
+        assert_eq!(index.insert(r"c:\MNT\über\", 3), Some(2));
+        assert_eq!(index.insert(r"\\server\share", 4), None);
+        assert_eq!(index.len(), 3);
+        assert_eq!(index.matcher(), PathMatcher::windows());
+
+        assert_eq!(index.longest_match(r"C:\mnt\ÜBER\x"), Some(&3));
+        assert_eq!(index.longest_match(r"\\?\C:\Mnt\Über"), Some(&3));
+        assert_eq!(index.longest_match(r"C:\Mnt\Überall"), Some(&1));
+        assert_eq!(index.longest_match(r"C:\Mnt"), Some(&1));
+        assert_eq!(index.longest_match(r"\\?\UNC\SERVER\Share\dir"), Some(&4));
+        assert_eq!(index.longest_match(r"\server\share\dir"), None);
+        assert_eq!(index.longest_match(r"D:\"), None);
+    }
+
+    #[test]
+    fn test_mount_index_matches_linear_scan() {
+        let mount_points = [
+            "/", "/a/", "/a/b/", "/a/bc/", "/A/", "/a/b/c/d/", "/x/y/", "/Ä/", "/ä/ö/", "/.../", "/a/b/c/",
+        ];
+        let paths = [
+            "/", "/a", "/a/b", "/a/bc", "/a/bcd", "/A/b", "/a/b/c", "/a/b/c/d/e", "/x", "/x/y/z", "/ä/Ö/x",
+            "/Ä/ö", "/.../x", "/a//b", "relative", "",
+        ];
+
+        for matcher in [
+            PathMatcher::unix(),
+            PathMatcher::unix().with_case_folding(CaseFolding::Ascii),
+            PathMatcher::windows(),
+            PathMatcher::windows().with_case_folding(CaseFolding::Exact),
+        ] {
+            let mut index = MountIndex::new(matcher);
+            for mount_point in mount_points {
+                index.insert(mount_point, matcher.key(mount_point));
+            }
+
+            for path in paths {
+                let expected = matcher.longest_match(path, mount_points).map(|m| matcher.key(m));
+                assert_eq!(index.longest_match(path), expected.as_ref(), "{matcher:?} {path}");
+            }
+        }
+    }
+}
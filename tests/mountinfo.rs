@@ -0,0 +1,76 @@
+#[cfg(test)]
+mod test {
+    use samevol::mountinfo;
+
+    #[test]
+    fn test_parse_line() {
+        let line = b"36 35 98:0 /mnt1 /mnt/parent rw,noatime master:1 - ext3 /dev/root rw,errors=continue";
+        let entry = mountinfo::parse_line(line).unwrap();
+
+        assert_eq!(entry.mount_id, 36);
+        assert_eq!(entry.parent_id, 35);
+        assert_eq!((entry.major, entry.minor), (98, 0));
+        assert_eq!(entry.root, b"/mnt1");
+        assert_eq!(entry.mount_point, b"/mnt/parent");
+        assert_eq!(entry.mount_options, "rw,noatime");
+        assert_eq!(entry.optional_fields, vec!["master:1"]);
+        assert_eq!(entry.fs_type, "ext3");
+        assert_eq!(entry.mount_source, "/dev/root");
+        assert_eq!(entry.super_options, "rw,errors=continue");
+    }
+
+    #[test]
+    fn test_parse_without_optional_fields() {
+        let line = b"25 1 0:22 / /dev/shm rw - tmpfs tmpfs rw";
+        let entry = mountinfo::parse_line(line).unwrap();
+        assert!(entry.optional_fields.is_empty());
+        assert_eq!(entry.device_id(), "0:22");
+    }
+
+    #[test]
+    fn test_parse_escaped_mount_point() {
+        let line = br"40 22 8:17 / /media/usb\040disk\134x rw shared:3 - vfat /dev/sdb1 rw";
+        let entry = mountinfo::parse_line(line).unwrap();
+        assert_eq!(entry.mount_point, br"/media/usb disk\x");
+    }
+
+    #[test]
+    fn test_parse_non_utf8_mount_point() {
+        let line = b"41 22 8:18 / /mnt/\xff rw - ext4 /dev/sdb2 rw";
+        let entry = mountinfo::parse_line(line).unwrap();
+        assert_eq!(entry.mount_point, b"/mnt/\xff");
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed() {
+        assert!(mountinfo::parse_line(b"36 35 98:0 /mnt1 /mnt/parent rw").is_err());
+        assert!(mountinfo::parse_line(b"x 35 98:0 / / rw - ext4 /dev/sda1 rw").is_err());
+        assert!(mountinfo::parse_line(b"36 35 98 / / rw - ext4 /dev/sda1 rw").is_err());
+
+        let err = mountinfo::parse(b"22 1 8:1 / / rw - ext4 /dev/sda1 rw\n\nbroken\n").unwrap_err();
+        assert!(err.to_string().contains("line 3"));
+    }
+
+    #[test]
+    fn test_unescape_keeps_malformed_sequences() {
+        assert_eq!(mountinfo::unescape(br"a\09b"), br"a\09b");
+        assert_eq!(mountinfo::unescape(br"tail\04"), br"tail\04");
+    }
+
+    #[test]
+    fn test_volumes() {
+        let content = b"22 1 8:1 / / rw - ext4 /dev/sda1 rw\n\
+                        23 22 8:1 /srv /srv rw - ext4 /dev/sda1 rw\n\
+                        24 22 0:40 / /tmp rw - tmpfs tmpfs rw\n\
+                        25 22 0:41 / /tmp rw - tmpfs tmpfs rw\n\
+                        26 22 0:40 / /run/shm rw - tmpfs tmpfs rw\n";
+        let volumes = mountinfo::volumes(&mountinfo::parse(content).unwrap());
+
+        // 被覆盖的挂载不计入，卷按首个可见挂载的顺序排列
+        let ids: Vec<&str> = volumes.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, ["8:1", "0:41", "0:40"]);
+        assert_eq!(volumes[0].1, [b"/".to_vec(), b"/srv".to_vec()]);
+        assert_eq!(volumes[1].1, [b"/tmp".to_vec()]);
+        assert_eq!(volumes[2].1, [b"/run/shm".to_vec()]);
+    }
+}
@@ -0,0 +1,44 @@
+#[cfg(test)]
+mod test {
+    use samevol::mountvol;
+
+    #[test]
+    fn test_parse() {
+        let output = "Usage text\r\n\r\n    \\\\?\\Volume{e8a7f3c2-0000-0000-0000-100000000000}\\\r\n        C:\\\r\n\r\n    \
+                      \\\\?\\volume{e8a7f3c2-0000-0000-0000-200000000000}\\\r\n        *** 没有装入点 ***\r\n";
+        let volumes = mountvol::parse(output).unwrap();
+        assert_eq!(volumes.len(), 2);
+        assert_eq!(volumes[0].0, r"\\?\Volume{e8a7f3c2-0000-0000-0000-100000000000}\");
+        assert_eq!(volumes[0].1, [r"C:\"]);
+        assert_eq!(volumes[1].0, r"\\?\volume{e8a7f3c2-0000-0000-0000-200000000000}\");
+        assert!(volumes[1].1.is_empty());
+    }
+
+    #[test]
+    fn test_parse_without_volumes() {
+        let err = mountvol::parse("MOUNTVOL [drive:]path VolumeName\n").unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert!(mountvol::parse("").is_err());
+    }
+
+    #[test]
+    fn test_parse_malformed_volume() {
+        // 损坏的卷 GUID 路径不会被当作上一个卷的挂载点
+        let output = "\\\\?\\Volume{e8a7f3c2-0000-0000-0000-100000000000}\\\n    C:\\\n\\\\?\\Volume{e8a7f3c2-0000\n    D:\\\n";
+        let err = mountvol::parse(output).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert!(mountvol::parse("\\\\?\\Volume{not-a-guid}\\\n    C:\\\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_sample() {
+        let volumes = mountvol::parse(include_str!("data/mountvol.txt")).unwrap();
+        assert_eq!(volumes.len(), 7);
+        assert!(volumes[0].1.is_empty());
+        assert_eq!(volumes[1], (r"\\?\Volume{5e1f9c7a-2b4d-4c6e-8a13-9d0b7e2f4c02}\".to_owned(), vec![r"C:\".to_owned()]));
+        assert_eq!(volumes[4].1, [r"C:\Mnt\VHD22\", r"E:\"]);
+        assert_eq!(volumes[5].1, [r"D:\Vdisks\Wechat\"]);
+        // 用法说明中的行不会被当作挂载点
+        assert!(volumes.iter().flat_map(|(_, mount_points)| mount_points).all(|mount_point| mount_point.ends_with('\\')));
+    }
+}
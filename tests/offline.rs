@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod test {
+    use samevol::matching::PathMatcher;
+    use samevol::*;
+
+    const MOUNTINFO: &[u8] = b"22 1 8:1 / / rw,relatime shared:1 - ext4 /dev/sda1 rw\n\
+        23 22 0:21 / /proc rw,nosuid shared:12 - proc proc rw\n\
+        30 22 8:17 / /home rw,relatime shared:2 - ext4 /dev/sdb1 rw\n\
+        31 30 8:33 / /home/user/my\\040data rw - ext4 /dev/sdc1 rw\n\
+        32 22 8:1 /srv /srv rw - ext4 /dev/sda1 rw\n\
+        33 22 0:40 / /tmp rw - tmpfs tmpfs rw\n\
+        34 22 0:41 / /tmp rw - tmpfs tmpfs rw\n";
+
+    const MOUNTVOL: &str = r"Creates, deletes, or lists a volume mount point.
+
+MOUNTVOL [drive:]path VolumeName
+MOUNTVOL [drive:]path /D
+
+Possible values for VolumeName along with current mount points are:
+
+    \\?\Volume{E8A7F3C2-0000-0000-0000-100000000000}\
+        C:\
+
+    \\?\Volume{e8a7f3c2-0000-0000-0000-200000000000}\
+        D:\
+        C:\Mnt\Data\
+
+    \\?\Volume{e8a7f3c2-0000-0000-0000-300000000000}\
+        *** NO MOUNT POINTS ***
+
+";
+
+    #[test]
+    fn test_from_mountinfo() {
+        let resolver = Resolver::builder().backend(OfflineBackend::from_mountinfo(MOUNTINFO).unwrap()).build();
+
+        assert_eq!(resolver.resolve_device_path("/etc/hosts").unwrap(), "8:1");
+        assert_eq!(resolver.resolve_device_path("/home/user/my data/file").unwrap(), "8:33");
+        assert_eq!(resolver.resolve_device_path("/home/user/my").unwrap(), "8:17");
+        // 被覆盖的挂载不可见
+        assert_eq!(resolver.resolve_device_path("/tmp/x").unwrap(), "0:41");
+        // 按字面处理 `..`
+        assert_eq!(resolver.resolve_device_path("/home/user/../../srv/www").unwrap(), "8:1");
+
+        assert!(resolver.is_same_vol("/etc", "/srv/www"));
+        assert!(!resolver.is_same_vol("/etc", "/home"));
+        // 同一卷的不同挂载之间无法重命名
+        assert!(!resolver.can_rename("/etc/a", "/srv/a"));
+
+        assert_eq!(resolver.volumes().unwrap().len(), 5);
+        assert!(OfflineBackend::from_mountinfo(b"not a mountinfo line").is_err());
+    }
+
+    #[test]
+    fn test_from_mountvol() {
+        let backend = OfflineBackend::from_mountvol(MOUNTVOL).unwrap().with_current_dir(r"C:\Mnt");
+        let resolver = Resolver::builder().backend(backend).build();
+
+        let system = r"\\?\Volume{e8a7f3c2-0000-0000-0000-100000000000}\";
+        let data = r"\\?\Volume{e8a7f3c2-0000-0000-0000-200000000000}\";
+        assert_eq!(resolver.resolve_device_path(r"C:\Windows").unwrap(), system);
+        assert_eq!(resolver.resolve_device_path(r"c:\mnt\data\file").unwrap(), data);
+        assert_eq!(resolver.resolve_device_path(r"Data\file").unwrap(), data);
+        assert!(resolver.is_same_vol(r"D:\a", r"C:\Mnt\Data\b"));
+        assert!(resolver.can_rename(r"D:\a", r"C:\Mnt\Data\b"));
+        assert!(resolver.resolve_device_path(r"E:\x").is_none());
+
+        let volumes = resolver.volumes().unwrap();
+        assert_eq!(volumes.len(), 3);
+        assert!(volumes.mount_points(r"\\?\Volume{e8a7f3c2-0000-0000-0000-300000000000}\").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_from_volumes() {
+        let live = Resolver::builder()
+            .backend(FakeBackend::windows().with_volume("C", [r"C:\"]).with_volume("W", [r"D:\Vdisks\Wechat"]))
+            .build();
+        let volumes = live.volumes().unwrap();
+
+        // 由快照重放的结果与原解析器一致
+        let resolver = Resolver::builder().backend(OfflineBackend::from_volumes(&volumes)).build();
+        assert_eq!(resolver.path_matcher(), PathMatcher::windows());
+        for path in [r"C:\a", r"D:\Vdisks\Wechat\b", r"D:\Vdisks\other"] {
+            assert_eq!(resolver.resolve_device_path(path), live.resolve_device_path(path));
+        }
+
+        let backend = OfflineBackend::new(PathMatcher::unix()).with_volume("8:1", ["/"]).with_volume("8:2", ["/data"]);
+        let resolver = Resolver::builder().backend(backend).build();
+        assert!(!resolver.is_same_vol("/a", "/data/b"));
+        assert!(resolver.volume_metadata("/a").is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_from_json() {
+        let json = r#"{
+            "version": 1,
+            "path_matcher": { "syntax": "unix", "case_folding": "exact" },
+            "volumes": [
+                { "id": "8:1", "mount_points": ["/"] },
+                { "id": "8:17", "mount_points": ["/home"] }
+            ]
+        }"#;
+        let volumes = Volumes::from_json(json).unwrap();
+        let resolver = Resolver::builder().backend(OfflineBackend::from_volumes(&volumes)).build();
+        assert_eq!(resolver.resolve_device_path("/home/a").unwrap(), "8:17");
+        assert_eq!(resolver.volumes().unwrap().to_json().unwrap(), volumes.to_json().unwrap());
+    }
+}
@@ -0,0 +1,98 @@
+#[cfg(all(test, feature = "powershell"))]
+mod test {
+    use std::collections::HashMap;
+
+    use samevol::matching::PathMatcher;
+    use samevol::{OfflineBackend, Resolver, mountvol, powershell};
+
+    const GET_VOLUME: &str = include_str!("data/get-volume.json");
+    const GET_VOLUME_SINGLE: &str = include_str!("data/get-volume-single.json");
+    const GET_PARTITION: &str = include_str!("data/get-partition.json");
+    const MOUNTVOL: &str = include_str!("data/mountvol.txt");
+
+    /// 以解析器枚举结果的形式比较：卷按标识排列，挂载点规范化
+    fn table(volumes: Vec<(String, Vec<String>)>) -> HashMap<String, Vec<String>> {
+        let backend = volumes
+            .into_iter()
+            .fold(OfflineBackend::new(PathMatcher::windows()), |backend, (id, mount_points)| {
+                backend.with_volume(&id, mount_points)
+            });
+        let resolver = Resolver::builder().backend(backend).build();
+        resolver
+            .volumes()
+            .unwrap()
+            .iter()
+            .map(|volume| {
+                let mount_points = volume.mount_points().iter().map(ToString::to_string).collect();
+                (volume.id().to_string(), mount_points)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_volumes() {
+        let volumes = powershell::parse_volumes(GET_VOLUME).unwrap();
+        assert_eq!(volumes.len(), 7);
+        assert_eq!(volumes[0], (r"\\?\Volume{5e1f9c7a-2b4d-4c6e-8a13-9d0b7e2f4c02}\".to_owned(), vec![r"C:\".to_owned()]));
+        // 没有驱动器号的卷
+        assert!(volumes[3].1.is_empty());
+
+        // 单个卷时输出对象而非数组
+        let volumes = powershell::parse_volumes(GET_VOLUME_SINGLE).unwrap();
+        assert_eq!(volumes, [(r"\\?\Volume{d27b4f8e-9a1c-4e3d-a6f5-7b8c2e9d0f04}\".to_owned(), vec![r"D:\".to_owned()])]);
+    }
+
+    #[test]
+    fn test_parse_partitions() {
+        let volumes = powershell::parse_partitions(GET_PARTITION).unwrap();
+        // 微软保留分区没有卷
+        assert_eq!(volumes.len(), 6);
+        assert!(volumes[0].1.is_empty());
+        assert_eq!(volumes[1].1, [r"C:\"]);
+        assert_eq!(volumes[4].1, [r"C:\Mnt\VHD22\", r"E:\"]);
+        assert_eq!(volumes[5].1, [r"D:\Vdisks\Wechat\"]);
+    }
+
+    #[test]
+    fn test_same_table_as_mountvol() {
+        let mountvol = table(mountvol::parse(MOUNTVOL).unwrap());
+        assert_eq!(mountvol.len(), 7);
+
+        // `Get-Partition` 不含光驱等非分区卷，其余与 `mountvol` 一致
+        let partitions = table(powershell::parse_partitions(GET_PARTITION).unwrap());
+        assert_eq!(partitions.len(), 6);
+        for (id, mount_points) in &partitions {
+            assert_eq!(&mountvol[id], mount_points, "{}", id);
+        }
+
+        // `Get-Volume` 只包含驱动器号
+        let volumes = table(powershell::parse_volumes(GET_VOLUME).unwrap());
+        assert_eq!(volumes.len(), 7);
+        for (id, mount_points) in &volumes {
+            let letters: Vec<&String> = mountvol[id].iter().filter(|mount_point| mount_point.len() == 3).collect();
+            assert_eq!(mount_points.iter().collect::<Vec<_>>(), letters, "{}", id);
+        }
+    }
+
+    #[test]
+    fn test_parse_variants() {
+        // 字节顺序标记、数字形式的驱动器号以及缺失的访问路径
+        let json = "\u{feff}[
+            { \"Path\": \"\\\\\\\\?\\\\volume{D27B4F8E-9A1C-4E3D-A6F5-7B8C2E9D0F04}\\\\\", \"DriveLetter\": 100 },
+            { \"Path\": \"\\\\\\\\server\\\\share\\\\\", \"UniqueId\": \"\\\\\\\\?\\\\Volume{5e1f9c7a-2b4d-4c6e-8a13-9d0b7e2f4c02}\\\\\", \"DriveLetter\": null },
+            { \"Path\": null, \"DriveLetter\": \"Z\" }
+        ]";
+        let volumes = powershell::parse_volumes(json).unwrap();
+        assert_eq!(volumes.len(), 2);
+        assert_eq!(volumes[0].1, [r"D:\"]);
+        assert_eq!(volumes[1].0, r"\\?\Volume{5e1f9c7a-2b4d-4c6e-8a13-9d0b7e2f4c02}\");
+
+        let json = r#"{ "DriveLetter": "G", "AccessPaths": ["\\\\?\\Volume{5e1f9c7a-2b4d-4c6e-8a13-9d0b7e2f4c02}\\"] }"#;
+        assert_eq!(powershell::parse_partitions(json).unwrap()[0].1, [r"G:\"]);
+        assert!(powershell::parse_partitions(r#"{ "AccessPaths": null }"#).unwrap().is_empty());
+
+        let err = powershell::parse_volumes("Get-Volume : Access denied").unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert!(powershell::parse_partitions("42").is_err());
+    }
+}
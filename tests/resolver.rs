@@ -0,0 +1,283 @@
+#[cfg(test)]
+mod test {
+    use std::io;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use samevol::*;
+
+    fn unix_layout() -> Arc<FakeBackend> {
+        Arc::new(
+            FakeBackend::unix()
+                .with_volume("8:1", ["/"])
+                .with_volume("8:17", ["/home"])
+                .with_current_dir("/home/user"),
+        )
+    }
+
+    #[test]
+    fn test_independent_instances() {
+        let resolver1 = Resolver::builder().backend(unix_layout()).build();
+        let resolver2 = Resolver::builder()
+            .backend(FakeBackend::unix().with_volume("0:42", ["/"]))
+            .build();
+
+        assert_eq!(resolver1.resolve_device_path("docs").as_deref(), Some("8:17"));
+        assert_eq!(resolver2.resolve_device_path("/home/user/docs").as_deref(), Some("0:42"));
+        assert!(resolver1.is_same_vol("/etc", "/usr/lib"));
+        assert!(!resolver1.is_same_vol("/etc", "."));
+    }
+
+    #[test]
+    fn test_manual_refresh() {
+        let backend = unix_layout();
+        let resolver = Resolver::builder().backend(backend.clone()).build();
+        assert_eq!(resolver.refresh_policy(), RefreshPolicy::Manual);
+        assert_eq!(resolver.resolve_device_path("/mnt/usb").as_deref(), Some("8:1"));
+
+        backend.mount("8:33", "/mnt/usb");
+        assert_eq!(resolver.resolve_device_path("/mnt/usb").as_deref(), Some("8:1"));
+
+        assert_eq!(resolver.reinitialize().unwrap(), 3);
+        assert_eq!(resolver.resolve_device_path("/mnt/usb").as_deref(), Some("8:33"));
+    }
+
+    #[test]
+    fn test_refresh_on_miss() {
+        let backend = Arc::new(FakeBackend::windows().with_volume("C", [r"C:\"]));
+        let resolver = Resolver::builder()
+            .backend(backend.clone())
+            .refresh_policy(RefreshPolicy::OnMiss)
+            .build();
+        assert_eq!(resolver.resolve_device_path(r"E:\data"), None);
+
+        backend.mount("E", r"E:\");
+        assert_eq!(resolver.resolve_device_path(r"E:\data").as_deref(), Some("E"));
+    }
+
+    #[test]
+    fn test_refresh_interval() {
+        let backend = unix_layout();
+        let resolver = Resolver::builder()
+            .backend(backend.clone())
+            .refresh_policy(RefreshPolicy::Interval(Duration::ZERO))
+            .build();
+        assert_eq!(resolver.resolve_device_path("/mnt/usb").as_deref(), Some("8:1"));
+
+        backend.mount("8:33", "/mnt/usb");
+        assert_eq!(resolver.resolve_device_path("/mnt/usb").as_deref(), Some("8:33"));
+
+        // 刷新失败时保留原有映射表
+        backend.fail_enumeration(io::ErrorKind::Other);
+        assert_eq!(resolver.resolve_device_path("/mnt/usb").as_deref(), Some("8:33"));
+    }
+
+    #[test]
+    fn test_failed_reinitialize_keeps_map() {
+        let backend = unix_layout();
+        let resolver = Resolver::builder().backend(backend.clone()).build();
+        assert_eq!(resolver.reinitialize().unwrap(), 2);
+
+        backend.fail_enumeration(io::ErrorKind::PermissionDenied);
+        let err = resolver.reinitialize().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        assert_eq!(resolver.resolve_device_path("/home").as_deref(), Some("8:17"));
+    }
+
+    #[test]
+    fn test_try_init() {
+        let backend = unix_layout();
+        backend.fail_enumeration(io::ErrorKind::PermissionDenied);
+        let resolver = Resolver::builder().backend(backend.clone()).build();
+        assert!(matches!(resolver.init_status(), InitStatus::Uninitialized));
+
+        let err = resolver.try_init().unwrap_err();
+        assert!(matches!(err, Error::VolumeEnumeration { .. }));
+        assert_eq!(resolver.init(), 0);
+        match resolver.init_status() {
+            InitStatus::Failed(e) => assert_eq!(e.kind(), io::ErrorKind::PermissionDenied),
+            status => panic!("unexpected status: {:?}", status),
+        }
+
+        backend.clear_failures();
+        assert_eq!(resolver.init(), 2);
+        assert_eq!(resolver.try_init().unwrap(), 2);
+        assert!(resolver.init_status().is_initialized());
+
+        // 已初始化时不会重建映射表
+        backend.mount("8:33", "/mnt/usb");
+        assert_eq!(resolver.try_init().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_retry_after_failed_init() {
+        let backend = unix_layout();
+        backend.fail_enumeration(io::ErrorKind::Other);
+        let resolver = Resolver::builder().backend(backend.clone()).build();
+        assert_eq!(resolver.resolve_device_path("/home"), None);
+
+        // 构建失败不会被永久缓存为空表
+        backend.clear_failures();
+        assert_eq!(resolver.resolve_device_path("/home").as_deref(), Some("8:17"));
+        assert!(matches!(resolver.init_status(), InitStatus::Initialized { mappings: 2 }));
+    }
+
+    #[test]
+    fn test_failed_refresh_after_init() {
+        let backend = unix_layout();
+        let resolver = Resolver::builder().backend(backend.clone()).build();
+        assert_eq!(resolver.try_init().unwrap(), 2);
+
+        // 已初始化后的刷新失败不影响初始化状态
+        backend.fail_enumeration(io::ErrorKind::Other);
+        assert!(resolver.reinitialize().is_err());
+        assert!(resolver.init_status().is_initialized());
+    }
+
+    #[test]
+    fn test_concurrent_lookups_during_refresh() {
+        let backend = unix_layout();
+        let resolver = Arc::new(Resolver::builder().backend(backend.clone()).build());
+        resolver.try_init().unwrap();
+
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                let resolver = &resolver;
+                scope.spawn(move || {
+                    for _ in 0..500 {
+                        // 刷新期间读取方始终能看到完整的映射表
+                        let id = resolver.try_resolve_device_path("/home/user/file").unwrap();
+                        assert!(id == "8:17" || id == "8:18");
+                        assert_eq!(resolver.try_resolve_device_path("/etc").unwrap(), "8:1");
+                    }
+                });
+            }
+
+            for i in 0..100 {
+                backend.mount(if i % 2 == 0 { "8:18" } else { "8:17" }, "/home");
+                resolver.reinitialize().unwrap();
+            }
+        });
+    }
+
+    #[test]
+    fn test_volumes() {
+        let backend = Arc::new(
+            FakeBackend::windows()
+                .with_volume(r"\\?\Volume{11111111-1111-1111-1111-111111111111}\", [r"C:\"])
+                .with_volume(r"\\?\Volume{22222222-2222-2222-2222-222222222222}\", [r"D:\", r"C:\Mount\D"])
+                .with_volume(r"\\?\Volume{33333333-3333-3333-3333-333333333333}\", [] as [&str; 0]),
+        );
+        let resolver = Resolver::builder().backend(backend.clone()).build();
+
+        let volumes = resolver.volumes().unwrap();
+        assert_eq!(volumes.len(), 3);
+        let listed: Vec<(&str, Vec<String>)> = volumes
+            .iter()
+            .map(|volume| (volume.id().as_str(), volume.mount_points().iter().map(|m| m.to_string()).collect()))
+            .collect();
+        assert_eq!(
+            listed,
+            [
+                (r"\\?\Volume{11111111-1111-1111-1111-111111111111}\", vec![r"C:\".to_owned()]),
+                (r"\\?\Volume{22222222-2222-2222-2222-222222222222}\", vec![r"D:\".to_owned(), r"C:\Mount\D\".to_owned()]),
+                (r"\\?\Volume{33333333-3333-3333-3333-333333333333}\", vec![]),
+            ]
+        );
+
+        // 反向查找：设备路径先规范化
+        let mount_points = volumes.mount_points(r"\\?\VOLUME{22222222-2222-2222-2222-222222222222}").unwrap();
+        assert_eq!(mount_points, [MountPoint::new(r"D:\"), MountPoint::new(r"C:\Mount\D")]);
+        assert_eq!(volumes.mount_points(r"\\?\Volume{33333333-3333-3333-3333-333333333333}\"), Some(&[][..]));
+        assert!(volumes.get(r"\\?\Volume{44444444-4444-4444-4444-444444444444}\").is_none());
+
+        let id = resolver.resolve_volume(r"C:\Mount\D\file").unwrap();
+        assert_eq!(resolver.mount_points(id.as_str()).unwrap().len(), 2);
+
+        // 快照不随之后的重建而改变
+        backend.unmount(r"C:\Mount\D");
+        resolver.reinitialize().unwrap();
+        assert_eq!(volumes.mount_points(id.as_str()).unwrap().len(), 2);
+        assert_eq!(resolver.mount_points(id.as_str()).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_volumes_failed_init() {
+        let backend = unix_layout();
+        backend.fail_enumeration(io::ErrorKind::PermissionDenied);
+        let resolver = Resolver::builder().backend(backend.clone()).build();
+        assert!(matches!(resolver.volumes(), Err(Error::VolumeEnumeration { .. })));
+        assert_eq!(resolver.mount_points("8:1"), None);
+
+        backend.clear_failures();
+        assert_eq!(resolver.mount_points("8:17").unwrap(), [MountPoint::new("/home")]);
+    }
+
+    #[test]
+    fn test_can_rename_bind_mounts() {
+        let resolver = Resolver::builder()
+            .backend(FakeBackend::unix().with_volume("8:1", ["/"]).with_volume("8:17", ["/home", "/mnt/home"]))
+            .build();
+
+        // 同一文件系统的两个绑定挂载：同卷，但重命名会失败（EXDEV）
+        assert!(resolver.is_same_vol("/home/a", "/mnt/home/b"));
+        assert!(!resolver.can_rename("/home/a", "/mnt/home/b"));
+        assert!(resolver.can_rename("/home/a", "/home/user/b"));
+        assert!(resolver.can_rename("/mnt/home/a", "/mnt/home/b"));
+        assert!(!resolver.can_rename("/etc/a", "/home/b"));
+        assert!(resolver.try_can_rename("/etc/a", "/usr/b").unwrap());
+    }
+
+    #[test]
+    fn test_can_rename_windows_mount_points() {
+        let backend = FakeBackend::windows()
+            .with_volume("C", [r"C:\"])
+            .with_volume("D", [r"D:\", r"C:\Mount\D"])
+            .with_path_error(r"C:\missing", io::ErrorKind::NotFound);
+        let resolver = Resolver::builder().backend(backend).build();
+
+        // Windows 上同一卷的不同挂载点之间可以直接重命名
+        assert!(resolver.can_rename(r"D:\a", r"c:\mount\d\b"));
+        assert!(!resolver.can_rename(r"C:\a", r"C:\Mount\D\b"));
+        assert!(matches!(resolver.try_can_rename(r"C:\a", r"C:\missing"), Err(Error::NotFound { .. })));
+        assert!(!resolver.can_rename(r"C:\a", r"C:\missing"));
+    }
+
+    #[test]
+    fn test_compare_granularity() {
+        let backend = FakeBackend::unix()
+            .with_volume("0:41", ["/", "/mnt/root"])
+            .with_volume("0:42", ["/home"])
+            .with_volume("8:17", ["/data"])
+            .with_volume("253:0", ["/srv"])
+            .with_volume("0:50", ["/tmp"])
+            // 同一 btrfs 分区上的两个文件系统
+            .with_block_device("0:41", "8:2")
+            .with_block_device("0:42", "8:2")
+            .with_physical_disks("0:41", ["sda"])
+            .with_physical_disks("0:42", ["sda"])
+            .with_physical_disks("8:17", ["sdb"])
+            .with_physical_disks("253:0", ["sda", "sdb"]);
+        let resolver = Resolver::builder().backend(backend).build();
+
+        let related = |path1, path2| {
+            [Granularity::Mount, Granularity::Filesystem, Granularity::BlockDevice, Granularity::PhysicalDisk]
+                .map(|granularity| resolver.try_compare(path1, path2, granularity).unwrap())
+        };
+        assert_eq!(related("/etc", "/usr"), [true; 4]);
+        assert_eq!(related("/etc", "/mnt/root/usr"), [false, true, true, true]);
+        assert_eq!(related("/etc", "/home/user"), [false, false, true, true]);
+        assert_eq!(related("/etc", "/data/x"), [false, false, false, false]);
+        assert_eq!(related("/srv/x", "/data/x"), [false, false, false, true]);
+        assert_eq!(related("/srv/x", "/home/x"), [false, false, false, true]);
+
+        // 未声明物理磁盘的卷无法判断
+        assert!(resolver.try_compare("/tmp/x", "/tmp/y", Granularity::PhysicalDisk).unwrap());
+        let err = resolver.try_compare("/tmp/x", "/etc", Granularity::PhysicalDisk).unwrap_err();
+        assert!(matches!(err, Error::Metadata { .. }));
+        assert!(!resolver.compare("/tmp/x", "/etc", Granularity::PhysicalDisk));
+        assert!(!resolver.compare("/tmp/x", "/etc", Granularity::BlockDevice));
+
+        assert!(Granularity::Mount < Granularity::PhysicalDisk);
+    }
+}
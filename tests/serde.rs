@@ -0,0 +1,139 @@
+#[cfg(all(test, feature = "serde"))]
+mod test {
+    use samevol::fs::MoveMethod;
+    use samevol::matching::{CaseFolding, PathMatcher};
+    use samevol::*;
+
+    fn resolver() -> Resolver {
+        Resolver::builder()
+            .backend(
+                FakeBackend::windows()
+                    .with_volume(r"\\?\Volume{e8a7f3c2-0000-0000-0000-100000000000}\", [r"C:\"])
+                    .with_volume(r"\\?\Volume{e8a7f3c2-0000-0000-0000-200000000000}\", [r"D:\", r"C:\Mnt\Data"]),
+            )
+            .build()
+    }
+
+    #[test]
+    fn test_volumes_round_trip() {
+        let volumes = resolver().volumes().unwrap();
+        let json = volumes.to_json().unwrap();
+
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["version"], Volumes::SCHEMA_VERSION);
+        assert_eq!(value["path_matcher"]["syntax"], "windows");
+        assert_eq!(value["path_matcher"]["case_folding"], "ntfs");
+        assert_eq!(value["volumes"][1]["mount_points"][1], r"C:\Mnt\Data\");
+
+        let loaded = Volumes::from_json(&json).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded.path_matcher(), PathMatcher::windows());
+        assert!(loaded.iter().eq(volumes.iter()));
+        assert_eq!(loaded.to_json().unwrap(), json);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_volumes_non_unicode_round_trip() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt as _;
+
+        let mount_point = OsStr::from_bytes(b"/mnt/\xff");
+        let resolver = Resolver::builder()
+            .backend(FakeBackend::unix().with_volume("8:1", ["/"]).with_volume("8:17", [mount_point]))
+            .build();
+        let volumes = resolver.volumes().unwrap();
+
+        // 非 Unicode 的挂载点以原始字节无损表示
+        let json = volumes.to_json().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["volumes"][0]["mount_points"][0], "/");
+        assert_eq!(value["volumes"][1]["mount_points"][0]["bytes"], serde_json::json!(b"/mnt/\xff/"));
+
+        let loaded = Volumes::from_json(&json).unwrap();
+        assert!(loaded.iter().eq(volumes.iter()));
+        assert_eq!(loaded.mount_points("8:17").unwrap()[0].as_os_str().as_bytes(), b"/mnt/\xff/");
+        let resolver = Resolver::builder().backend(OfflineBackend::from_volumes(&loaded)).build();
+        assert_eq!(resolver.resolve_device_path(OsStr::from_bytes(b"/mnt/\xff/file")).as_deref(), Some("8:17"));
+    }
+
+    #[test]
+    fn test_volumes_invalid() {
+        let matcher = r#""path_matcher": { "syntax": "unix", "case_folding": "exact" }"#;
+
+        // 版本不符
+        let json = format!(r#"{{ "version": 2, {}, "volumes": [] }}"#, matcher);
+        assert!(Volumes::from_json(&json).unwrap_err().to_string().contains("version"));
+
+        // 设备路径无效
+        let json = format!(r#"{{ "version": 1, {}, "volumes": [{{ "id": "", "mount_points": [] }}] }}"#, matcher);
+        assert!(Volumes::from_json(&json).is_err());
+
+        // 同一卷出现两次（规范化后相同）
+        let json = format!(
+            r#"{{ "version": 1, {}, "volumes": [{{ "id": "8:1", "mount_points": ["/"] }}, {{ "id": "08:01", "mount_points": [] }}] }}"#,
+            matcher
+        );
+        assert!(Volumes::from_json(&json).unwrap_err().to_string().contains("duplicate"));
+    }
+
+    #[test]
+    fn test_volumes_canonical() {
+        // 反序列化时与枚举时一样规范化
+        let json = r#"{
+            "version": 1,
+            "path_matcher": { "syntax": "windows", "case_folding": "ascii" },
+            "volumes": [{ "id": "\\\\?\\volume{E8A7F3C2-0000-0000-0000-100000000000}", "mount_points": ["C:"] }]
+        }"#;
+        let volumes = Volumes::from_json(json).unwrap();
+        assert_eq!(volumes.path_matcher().case_folding(), CaseFolding::Ascii);
+        let volume = volumes.iter().next().unwrap();
+        assert_eq!(volume.id().as_str(), r"\\?\Volume{e8a7f3c2-0000-0000-0000-100000000000}\");
+        assert_eq!(volume.mount_points()[0].as_os_str(), r"C:\");
+    }
+
+    #[test]
+    fn test_result_types() {
+        let resolution = resolver().batch().resolve(r"C:\Mnt\Data\file").unwrap();
+        let json = serde_json::to_string(&resolution).unwrap();
+        assert_eq!(
+            json,
+            r#"{"volume":"\\\\?\\Volume{e8a7f3c2-0000-0000-0000-200000000000}\\","mount_point":"C:\\Mnt\\Data\\"}"#
+        );
+        assert_eq!(serde_json::from_str::<Resolution>(&json).unwrap(), resolution);
+
+        let metadata = VolumeMetadata::new().with_fs_type("NTFS").with_capacity(100, 40, 30);
+        let json = serde_json::to_value(&metadata).unwrap();
+        assert_eq!(json["fs_type"], "NTFS");
+        assert!(json["label"].is_null());
+        assert_eq!(json["available_bytes"], 30);
+        assert_eq!(serde_json::from_value::<VolumeMetadata>(json).unwrap(), metadata);
+        assert!(serde_json::from_str::<VolumeMetadata>(r#"{ "total_bytes": 1 }"#).is_err());
+
+        assert_eq!(serde_json::to_string(&MoveMethod::Copied).unwrap(), r#""copied""#);
+        assert_eq!(serde_json::from_str::<VolumeId>(r#""08:017""#).unwrap().as_str(), "8:17");
+        assert!(serde_json::from_str::<DevicePath>(r#""\\?\Volume{bad}""#).is_err());
+        assert_eq!(serde_json::to_string(&Granularity::BlockDevice).unwrap(), r#""block_device""#);
+    }
+
+    #[test]
+    fn test_guid_and_mountinfo() {
+        use samevol::guid::{Guid, VolumeGuidPath};
+        use samevol::mountinfo::{self, MountInfo};
+
+        let path: VolumeGuidPath = r"\\?\Volume{E8A7F3C2-1234-5678-9ABC-DEF012345678}".parse().unwrap();
+        let json = serde_json::to_string(&path).unwrap();
+        assert_eq!(json, r#""\\\\?\\Volume{e8a7f3c2-1234-5678-9abc-def012345678}\\""#);
+        assert_eq!(serde_json::from_str::<VolumeGuidPath>(&json).unwrap(), path);
+
+        let json = serde_json::to_string(&path.guid()).unwrap();
+        assert_eq!(json, r#""e8a7f3c2-1234-5678-9abc-def012345678""#);
+        assert_eq!(serde_json::from_str::<Guid>(&json).unwrap(), path.guid());
+        assert!(serde_json::from_str::<Guid>(r#""not-a-guid""#).is_err());
+
+        let entry = mountinfo::parse_line(b"36 35 98:0 /mnt1 /mnt/parent rw,noatime master:1 - ext3 /dev/root rw").unwrap();
+        let json = serde_json::to_value(&entry).unwrap();
+        assert_eq!(json["mount_id"], 36);
+        assert_eq!(serde_json::from_value::<MountInfo>(json).unwrap(), entry);
+    }
+}
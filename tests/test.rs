@@ -1,4 +1,4 @@
-#[cfg(test)]
+#[cfg(all(test, windows))]
 mod test {
     use samevol::*;
 
@@ -27,4 +27,34 @@ mod test {
 
         assert_eq!(resolved_path1, resolved_path2);
     }
+
+    #[test]
+    fn test_unpaired_surrogate_path() {
+        use std::ffi::OsString;
+        use std::os::windows::ffi::OsStringExt as _;
+
+        // NTFS 允许文件名中包含未配对的代理项
+        let mut path = OsString::from(r"C:\Windows\");
+        path.push(OsString::from_wide(&[0xD800, b'x' as u16]));
+        assert!(is_same_vol(r"C:\Windows", &path));
+    }
+
+    #[test]
+    fn test_volume_metadata() {
+        let metadata = volume_metadata(r"C:\Windows").unwrap();
+        assert_eq!(metadata.fs_type(), Some("NTFS"));
+        assert!(metadata.serial_number().is_some());
+        assert!(metadata.total_bytes().unwrap() >= metadata.free_bytes().unwrap());
+    }
+
+    #[test]
+    fn test_can_rename() {
+        assert!(can_rename(r"C:\Windows\a", r"C:\Users\b"));
+    }
+
+    #[test]
+    fn test_compare() {
+        assert!(compare(r"C:\Windows", r"C:\Users", Granularity::PhysicalDisk));
+        assert!(!compare(r"D:\", r"D:\Vdisks\Wechat\another_file.txt", Granularity::BlockDevice));
+    }
 }
@@ -0,0 +1,95 @@
+#[cfg(test)]
+mod test {
+    use std::collections::{BTreeSet, HashMap};
+
+    use samevol::*;
+
+    const GUID_PATH: &str = r"\\?\Volume{e8a7f3c2-1234-5678-9abc-def012345678}\";
+
+    #[test]
+    fn test_device_path_volume_guid() {
+        for input in [
+            GUID_PATH,
+            r"\\?\Volume{e8a7f3c2-1234-5678-9abc-def012345678}",
+            r"\\?\VOLUME{E8A7F3C2-1234-5678-9ABC-DEF012345678}\",
+        ] {
+            let path: DevicePath = input.parse().unwrap();
+            assert_eq!(path.as_str(), GUID_PATH);
+            assert!(path.is_volume_guid());
+            assert_eq!(path.device_number(), None);
+        }
+
+        assert!(DevicePath::parse(r"\\?\Volume{e8a7f3c2}\").is_err());
+        assert!(DevicePath::parse(r"\\?\Volume{e8a7f3c2-1234-5678-9abc-def01234567g}\").is_err());
+        assert!(DevicePath::parse(r"\\?\Volume{e8a7f3c2-1234-5678-9abc-def012345678").is_err());
+    }
+
+    #[test]
+    fn test_device_path_device_number() {
+        let path: DevicePath = "008:017".parse().unwrap();
+        assert_eq!(path.to_string(), "8:17");
+        assert_eq!(path.device_number(), Some((8, 17)));
+        assert!(!path.is_volume_guid());
+    }
+
+    #[test]
+    fn test_device_path_other() {
+        let path: DevicePath = r"\\?\GLOBALROOT\Device\HarddiskVolumeShadowCopy1".parse().unwrap();
+        assert_eq!(path.as_str(), r"\\?\GLOBALROOT\Device\HarddiskVolumeShadowCopy1");
+        assert_eq!(path.device_number(), None);
+
+        let err = DevicePath::parse("").unwrap_err();
+        assert_eq!(err.input(), "");
+        assert!(DevicePath::parse("a b").is_err());
+    }
+
+    #[test]
+    fn test_volume_id() {
+        let id1: VolumeId = GUID_PATH.to_ascii_uppercase().parse().unwrap();
+        let id2 = DevicePath::parse(GUID_PATH).unwrap().volume_id();
+        assert_eq!(id1, id2);
+        assert_eq!(id1.to_string(), GUID_PATH);
+        assert_eq!(id1.device_path().as_str(), GUID_PATH);
+
+        let mut map = HashMap::new();
+        map.insert(id1.clone(), 1);
+        assert_eq!(map[GUID_PATH], 1);
+
+        let ids: BTreeSet<VolumeId> = ["8:2", "8:1", "8:1"].iter().map(|s| s.parse().unwrap()).collect();
+        assert_eq!(ids.iter().map(VolumeId::as_str).collect::<Vec<_>>(), ["8:1", "8:2"]);
+    }
+
+    #[test]
+    fn test_mount_point() {
+        assert_eq!(MountPoint::new(r"C:").as_os_str(), r"C:\");
+        assert_eq!(MountPoint::new(r"D:\Vdisks\Wechat").as_os_str(), r"D:\Vdisks\Wechat\");
+        assert_eq!(MountPoint::new(r"D:\").as_os_str(), r"D:\");
+        assert_eq!(MountPoint::new("/mnt/data").as_os_str(), "/mnt/data/");
+        assert_eq!(MountPoint::new("/").as_os_str(), "/");
+        assert!("".parse::<MountPoint>().is_err());
+
+        let mount_point = MountPoint::new(r"C:\foo");
+        assert!(mount_point.contains(r"C:\foo"));
+        assert!(mount_point.contains(r"C:\foo\bar"));
+        assert!(!mount_point.contains(r"C:\foobar"));
+        assert_eq!(mount_point.to_string(), r"C:\foo\");
+    }
+
+    #[test]
+    fn test_resolve_volume() {
+        let resolver = Resolver::builder()
+            .backend(FakeBackend::windows().with_volume(GUID_PATH, [r"C:\"]).with_volume("8:1", [r"D:\"]))
+            .build();
+
+        let id = resolver.resolve_volume(r"C:\Windows").unwrap();
+        assert_eq!(id.as_str(), GUID_PATH);
+        assert_eq!(resolver.try_resolve_volume(r"D:\data").unwrap().device_path().device_number(), Some((8, 1)));
+        assert!(resolver.resolve_volume(r"E:\data").is_none());
+    }
+
+    #[test]
+    fn test_invalid_device_path_from_backend() {
+        let resolver = Resolver::builder().backend(FakeBackend::unix().with_volume("", ["/"])).build();
+        assert!(matches!(resolver.try_init(), Err(Error::VolumeEnumeration { .. })));
+    }
+}
@@ -0,0 +1,129 @@
+#[cfg(test)]
+mod test {
+    use std::io;
+    use std::path::Path;
+
+    use samevol::winpath::*;
+
+    fn full_path(dirs: &WorkingDirs, path: &str) -> String {
+        dirs.full_path(path).unwrap().to_str().unwrap().to_owned()
+    }
+
+    #[test]
+    fn test_path_type() {
+        let cases = [
+            (r"\\?\C:\foo", PathType::Verbatim),
+            (r"\\.\C:\foo", PathType::Device),
+            (r"//?/C:/foo", PathType::Device),
+            (r"\\.", PathType::Device),
+            (r"\\server\share", PathType::Unc),
+            (r"//server/share", PathType::Unc),
+            (r"C:\foo", PathType::DriveAbsolute),
+            (r"c:/foo", PathType::DriveAbsolute),
+            (r"C:foo", PathType::DriveRelative),
+            (r"C:", PathType::DriveRelative),
+            (r"\foo", PathType::Rooted),
+            (r"foo", PathType::Relative),
+            (r"1:\foo", PathType::Relative),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(PathType::of(path), expected, "{path}");
+        }
+
+        assert!(PathType::Unc.is_absolute());
+        assert!(!PathType::DriveRelative.is_absolute());
+    }
+
+    #[test]
+    fn test_relative() {
+        let dirs = WorkingDirs::new(r"C:\Users\Public");
+        assert_eq!(full_path(&dirs, "foo"), r"C:\Users\Public\foo");
+        assert_eq!(full_path(&dirs, r".\foo\.\bar"), r"C:\Users\Public\foo\bar");
+        assert_eq!(full_path(&dirs, r"..\..\..\..\foo"), r"C:\foo");
+        assert_eq!(full_path(&dirs, "."), r"C:\Users\Public");
+        assert_eq!(full_path(&dirs, r"foo\"), r"C:\Users\Public\foo\");
+        assert_eq!(full_path(&dirs, r"\Windows"), r"C:\Windows");
+        assert_eq!(full_path(&dirs, r"\"), r"C:\");
+    }
+
+    #[test]
+    fn test_drive_relative() {
+        let dirs = WorkingDirs::new(r"C:\Users").with_drive_dir('d', r"D:\data");
+        assert_eq!(full_path(&dirs, "C:foo"), r"C:\Users\foo");
+        assert_eq!(full_path(&dirs, "c:foo"), r"C:\Users\foo");
+        assert_eq!(full_path(&dirs, "C:"), r"C:\Users");
+        assert_eq!(full_path(&dirs, r"D:logs\..\x"), r"D:\data\x");
+        assert_eq!(full_path(&dirs, "D:"), r"D:\data");
+        assert_eq!(full_path(&dirs, "E:foo"), r"E:\foo");
+        assert_eq!(full_path(&dirs, "E:"), r"E:\");
+    }
+
+    #[test]
+    fn test_separators() {
+        let dirs = WorkingDirs::new(r"C:\");
+        assert_eq!(full_path(&dirs, "C:/a//b///c"), r"C:\a\b\c");
+        assert_eq!(full_path(&dirs, "//server/share//dir"), r"\\server\share\dir");
+    }
+
+    #[test]
+    fn test_trailing_dots_and_spaces() {
+        let dirs = WorkingDirs::new(r"C:\");
+        assert_eq!(full_path(&dirs, r"C:\foo.\bar"), r"C:\foo\bar");
+        assert_eq!(full_path(&dirs, r"C:\foo..\bar"), r"C:\foo..\bar");
+        assert_eq!(full_path(&dirs, r"C:\foo\bar. . ."), r"C:\foo\bar");
+        assert_eq!(full_path(&dirs, r"C:\foo\bar \"), r"C:\foo\bar \");
+        assert_eq!(full_path(&dirs, r"C:\foo\..."), r"C:\foo");
+        assert_eq!(full_path(&dirs, r"C:\foo\...\"), r"C:\foo\...\");
+    }
+
+    #[test]
+    fn test_unc() {
+        let dirs = WorkingDirs::new(r"\\server\share\dir");
+        assert_eq!(full_path(&dirs, r"\\server\share\a\..\..\..\b"), r"\\server\share\b");
+        assert_eq!(full_path(&dirs, r"\\server\share"), r"\\server\share");
+        assert_eq!(full_path(&dirs, r"\\server\share\.."), r"\\server\share\");
+        assert_eq!(full_path(&dirs, "file"), r"\\server\share\dir\file");
+        assert_eq!(full_path(&dirs, r"\file"), r"\\server\share\file");
+        assert_eq!(full_path(&dirs, r"..\..\file"), r"\\server\share\file");
+    }
+
+    #[test]
+    fn test_device_and_verbatim() {
+        let dirs = WorkingDirs::new(r"C:\");
+        assert_eq!(full_path(&dirs, r"\\.\C:\a\..\..\b"), r"\\.\C:\b");
+        assert_eq!(full_path(&dirs, r"//./PhysicalDrive0"), r"\\.\PhysicalDrive0");
+        assert_eq!(full_path(&dirs, r"\\?\C:\a\..\b. "), r"\\?\C:\a\..\b. ");
+        assert_eq!(
+            full_path(&dirs, r"\\?\Volume{e8a7f3c2-1234-5678-9abc-def012345678}\x"),
+            r"\\?\Volume{e8a7f3c2-1234-5678-9abc-def012345678}\x"
+        );
+    }
+
+    #[test]
+    fn test_empty_path() {
+        let err = WorkingDirs::new(r"C:\").full_path("").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_set_current_dir() {
+        let mut dirs = WorkingDirs::new(r"C:\");
+        dirs.set_current_dir(r"D:\work");
+        dirs.set_drive_dir('C', r"C:\Windows");
+        dirs.set_drive_dir('1', r"C:\ignored");
+        assert_eq!(dirs.current_dir(), Path::new(r"D:\work"));
+        assert_eq!(full_path(&dirs, "C:System32"), r"C:\Windows\System32");
+        assert_eq!(full_path(&dirs, "D:x"), r"D:\work\x");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_unicode() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt as _;
+
+        let dirs = WorkingDirs::new(r"C:\");
+        let path = OsStr::from_bytes(b"C:\\caf\xe9\\.\\x");
+        assert_eq!(dirs.full_path(path).unwrap().as_os_str().as_bytes(), b"C:\\caf\xe9\\x");
+    }
+}